@@ -0,0 +1,329 @@
+use crate::csr::CsrGraph;
+use crate::dijkstra::{dijkstra_all, ShortestPathTree};
+use crate::graph::{Graph, Reversed};
+use crate::heuristic::Heuristic;
+use crate::rng::XorShift;
+use crate::weight::Weight;
+
+
+/// How [`Landmarks::new`] picks its landmarks.
+///
+/// [`Landmarks::new`]: struct.Landmarks.html#method.new
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Selection {
+    /// Nodes picked uniformly at random, seeded by a non-zero number
+    Random(u64),
+    /// Each landmark as far as possible from the ones picked before, starting with the node
+    /// farthest from node 0. Nodes the landmarks can not reach count as farthest.
+    Farthest,
+    /// Landmarks in regions the landmarks picked before cover badly: in the shortest path tree
+    /// of the node farthest from them, the leaf below the subtree whose distances they
+    /// underestimate most, not containing any of them.
+    Avoid,
+}
+
+
+/// Landmarks with their distances to and from all nodes, bounding the cost between any two
+/// nodes by the triangle inequality.
+///
+/// For a landmark `l` and nodes `v` and `t`, the cost `d(v, t)` is at least `d(v, l) - d(t, l)`
+/// and at least `d(l, t) - d(l, v)`. The best bound over all landmarks is a consistent heuristic
+/// for [`astar_path`] on graphs without coordinates, known as ALT (A*, landmarks, triangle
+/// inequality). When the bounds show a node can not reach the target at all, its estimate is
+/// the largest weight, so A* never queues it.
+///
+/// Distances from the landmarks are found by [`dijkstra_all`] on the graph, distances to them on
+/// the inverted graph: its [reverse neighbors] if it has them, a transposed copy otherwise.
+///
+/// # Example
+/// ```
+/// use vasa::alt::{Landmarks, Selection};
+/// use vasa::dijkstra::{astar_bidir_path, astar_path};
+/// use vasa::graph::AdjacencyList;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (2, 3, 1), (0, 3, 9), (3, 0, 1)]);
+/// let landmarks = Landmarks::new(&graph, 2, Selection::Farthest);
+/// assert_eq!(landmarks.landmarks().len(), 2);
+/// let path = astar_path(&graph, 0, 3, &landmarks.towards(3)).unwrap();
+/// assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+/// let path = astar_bidir_path(&graph, 0, 3, &landmarks.towards(3), &landmarks.away_from(0)).unwrap();
+/// assert_eq!(path.cost, 4);
+/// ```
+///
+/// [`astar_path`]: ../dijkstra/fn.astar_path.html
+/// [`dijkstra_all`]: ../dijkstra/fn.dijkstra_all.html
+/// [reverse neighbors]: ../graph/trait.Graph.html#method.reverse_neighbors
+#[derive(Debug, Clone)]
+pub struct Landmarks<W = usize> {
+    landmarks: Vec<usize>,
+    /// from[i][node] = distance from landmark `i` to `node`
+    from: Vec<Vec<Option<W>>>,
+    /// to[i][node] = distance from `node` to landmark `i`
+    to: Vec<Vec<Option<W>>>,
+}
+
+impl<W: Weight> Landmarks<W> {
+    /// Picking `count` landmarks of `graph`, or all nodes if there are fewer.
+    ///
+    /// # Panics
+    /// If a `Random` selection is seeded with zero.
+    pub fn new<G>(graph: &G, count: usize, selection: Selection) -> Landmarks<W>
+        where G: Graph<Weight = W>
+    {
+        if graph.node_count() == 0 {
+            return Landmarks { landmarks: Vec::new(), from: Vec::new(), to: Vec::new() };
+        }
+        // Distances to the landmarks are distances from them against the edges
+        if graph.reverse_neighbors(0).is_some() {
+            Landmarks::select(graph, &Reversed(graph), count, selection)
+        } else {
+            Landmarks::select(graph, &CsrGraph::transpose_of(graph), count, selection)
+        }
+    }
+
+    /// Picking landmarks, with `graph_inv` having the edges of `graph` reversed.
+    fn select<G, R>(graph: &G, graph_inv: &R, count: usize, selection: Selection) -> Landmarks<W>
+        where G: Graph<Weight = W>,
+              R: Graph<Weight = W>
+    {
+        let n = graph.node_count();
+        let count = count.min(n);
+        let mut landmarks = Landmarks { landmarks: Vec::new(), from: Vec::new(), to: Vec::new() };
+
+        match selection {
+            Selection::Random(seed) => {
+                let mut rng = XorShift::new(seed);
+                let mut picked = vec![false; n];
+                while landmarks.landmarks.len() < count {
+                    let node = rng.below(n);
+                    if !picked[node] {
+                        picked[node] = true;
+                        landmarks.add(graph, graph_inv, node);
+                    }
+                }
+            }
+            Selection::Farthest => {
+                let first = landmarks.farthest(&dijkstra_all(graph, 0));
+                while landmarks.landmarks.len() < count {
+                    let node = match landmarks.landmarks.len() {
+                        0 => first,
+                        _ => landmarks.farthest_from_landmarks(),
+                    };
+                    landmarks.add(graph, graph_inv, node);
+                }
+            }
+            Selection::Avoid => {
+                while landmarks.landmarks.len() < count {
+                    let node = match landmarks.landmarks.len() {
+                        0 => landmarks.farthest(&dijkstra_all(graph, 0)),
+                        _ => {
+                            let root = landmarks.farthest_from_landmarks();
+                            landmarks.avoid(&dijkstra_all(graph, root))
+                        }
+                    };
+                    landmarks.add(graph, graph_inv, node);
+                }
+            }
+        }
+
+        landmarks
+    }
+
+    /// Adding `node` as landmark, with its distance tables.
+    fn add<G, R>(&mut self, graph: &G, graph_inv: &R, node: usize)
+        where G: Graph<Weight = W>,
+              R: Graph<Weight = W>
+    {
+        let from = dijkstra_all(graph, node);
+        let to = dijkstra_all(graph_inv, node);
+        self.landmarks.push(node);
+        self.from.push((0..graph.node_count()).map(|v| from.distance_to(v)).collect());
+        self.to.push((0..graph.node_count()).map(|v| to.distance_to(v)).collect());
+    }
+
+    /// Node of the tree farthest from its source, preferring nodes that are not landmarks.
+    fn farthest(&self, tree: &ShortestPathTree<W>) -> usize {
+        tree.settled()
+            .filter(|(node, _)| !self.landmarks.contains(node))
+            .last()
+            .map_or(tree.source(), |(node, _)| node)
+    }
+
+    /// Node that is not a landmark maximizing the distance from the closest landmark, with nodes
+    /// no landmark reaches farthest of all.
+    fn farthest_from_landmarks(&self) -> usize {
+        let n = self.from[0].len();
+        (0..n)
+            .filter(|node| !self.landmarks.contains(node))
+            .max_by_key(|&node| {
+                let closest = self.from.iter().filter_map(|from| from[node]).min();
+                (closest.is_none(), closest)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Leaf of `tree` below the subtree without landmarks whose distances the current landmarks
+    /// underestimate most.
+    fn avoid(&self, tree: &ShortestPathTree<W>) -> usize {
+        let n = self.from[0].len();
+        let root = tree.source();
+        let order: Vec<usize> = tree.settled().map(|(node, _)| node).collect();
+        let mut children = vec![Vec::new(); n];
+        for &node in &order {
+            if let Some(parent) = tree.predecessor(node) {
+                children[parent].push(node);
+            }
+        }
+
+        // size[node] = sum of the errors `d(root, v) - bound(root, v)` in the subtree below
+        // `node`, `None` if it contains a landmark
+        let lower_bound = self.away_from(root);
+        let mut size: Vec<Option<W>> = vec![Some(W::zero()); n];
+        for &node in order.iter().rev() {
+            if self.landmarks.contains(&node) {
+                size[node] = None;
+            }
+            let error = tree.distance_to(node).unwrap().checked_sub(lower_bound.estimate(node));
+            size[node] = size[node].map(|s| s.saturating_add(error.unwrap_or_else(W::zero)));
+            if let Some(parent) = tree.predecessor(node) {
+                size[parent] = match (size[parent], size[node]) {
+                    (Some(p), Some(s)) => Some(p.saturating_add(s)),
+                    _ => None,
+                };
+            }
+        }
+
+        let mut node = root;
+        while let Some(&child) = children[node].iter()
+            .filter(|&&child| size[child].is_some_and(|s| s > W::zero()))
+            .max_by_key(|&&child| size[child]) {
+            node = child;
+        }
+        if self.landmarks.contains(&node) { self.farthest_from_landmarks() } else { node }
+    }
+
+    /// Nodes picked as landmarks.
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// Heuristic estimating the cost from each node to `goal`, for searches towards `goal`.
+    pub fn towards(&self, goal: usize) -> LandmarkHeuristic<'_, W> {
+        LandmarkHeuristic { landmarks: self, target: goal, backward: false }
+    }
+
+    /// Heuristic estimating the cost from `start` to each node, for searches back towards
+    /// `start`, such as the backward search of [`astar_bidir_path`].
+    ///
+    /// [`astar_bidir_path`]: ../dijkstra/fn.astar_bidir_path.html
+    pub fn away_from(&self, start: usize) -> LandmarkHeuristic<'_, W> {
+        LandmarkHeuristic { landmarks: self, target: start, backward: true }
+    }
+
+    /// Lower bound of the cost from `u` to `v`, the largest weight if `v` is unreachable.
+    fn lower_bound(&self, u: usize, v: usize) -> W {
+        let mut bound = W::zero();
+        for (from, to) in self.from.iter().zip(&self.to) {
+            // d(u, v) >= d(u, l) - d(v, l)
+            match (to[u], to[v]) {
+                (Some(ul), Some(vl)) => bound = bound.max(ul.checked_sub(vl).unwrap_or(bound)),
+                (None, Some(_)) => return W::max_value(),
+                (_, None) => {}
+            }
+            // d(u, v) >= d(l, v) - d(l, u)
+            match (from[u], from[v]) {
+                (Some(lu), Some(lv)) => bound = bound.max(lv.checked_sub(lu).unwrap_or(bound)),
+                (Some(_), None) => return W::max_value(),
+                (None, _) => {}
+            }
+        }
+        bound
+    }
+}
+
+
+/// Estimate of [`Landmarks`] towards a goal, or from a start.
+///
+/// [`Landmarks`]: struct.Landmarks.html
+#[derive(Debug, Clone, Copy)]
+pub struct LandmarkHeuristic<'a, W> {
+    landmarks: &'a Landmarks<W>,
+    target: usize,
+    backward: bool,
+}
+
+impl<W: Weight> Heuristic<W> for LandmarkHeuristic<'_, W> {
+    fn estimate(&self, node: usize) -> W {
+        if self.backward {
+            self.landmarks.lower_bound(self.target, node)
+        } else {
+            self.landmarks.lower_bound(node, self.target)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::{astar_bidir_path, astar_path, dijkstra};
+    use crate::graph::AdjacencyList;
+    use crate::testutil::{random_graph, XorShift};
+
+    const SELECTIONS: [Selection; 3] = [Selection::Random(7), Selection::Farthest, Selection::Avoid];
+
+    #[test]
+    fn admissible() {
+        let mut rng = XorShift::new(22);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 20, 60, 10);
+            let n = graph.node_count();
+            for &selection in &SELECTIONS {
+                let landmarks = Landmarks::new(&graph, 3, selection);
+                let mut picked = landmarks.landmarks().to_vec();
+                picked.sort();
+                picked.dedup();
+                assert_eq!(picked.len(), 3.min(n), "{:?}", selection);
+
+                for start in 0..n {
+                    for goal in 0..n {
+                        if let Some(cost) = dijkstra(&graph, start, goal) {
+                            let estimate = landmarks.towards(goal).estimate(start);
+                            assert!(estimate <= cost, "{} > {}", estimate, cost);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn astar_like_dijkstra() {
+        let mut rng = XorShift::new(2022);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 30, 100, 10);
+            let n = graph.node_count();
+            for &selection in &SELECTIONS {
+                let landmarks = Landmarks::new(&graph, 4, selection);
+                for _ in 0..10 {
+                    let (start, goal) = (rng.below(n), rng.below(n));
+                    let expected = dijkstra(&graph, start, goal);
+                    let path = astar_path(&graph, start, goal, &landmarks.towards(goal));
+                    assert_eq!(path.map(|path| path.cost), expected);
+                    let path = astar_bidir_path(&graph, start, goal,
+                                                &landmarks.towards(goal), &landmarks.away_from(start));
+                    assert_eq!(path.map(|path| path.cost), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_at_landmark() {
+        // Along a line, a landmark at the end gives exact distances
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 2), (1, 2, 3), (2, 3, 4), (3, 2, 4), (2, 1, 3), (1, 0, 2)]);
+        let landmarks = Landmarks::new(&graph, 1, Selection::Farthest);
+        assert_eq!(landmarks.landmarks(), &[3]);
+        let h = landmarks.towards(1);
+        assert_eq!((0..4).map(|node| h.estimate(node)).collect::<Vec<_>>(), vec![2, 0, 3, 7]);
+    }
+}
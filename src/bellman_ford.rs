@@ -0,0 +1,343 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::dijkstra::ShortestPathTree;
+use crate::fifo::BoundedFIFO;
+use crate::graph::{Edge, Graph};
+use crate::weight::Weight;
+
+
+/// Error for graphs where a cycle of negative cost makes shortest paths meaningless.
+///
+/// Going around the cycle once more always makes a path cheaper, so there is no shortest one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle {
+    /// Nodes of the cycle in order, each with an edge to the next, and the last one with an edge
+    /// back to the first
+    pub nodes: Vec<usize>,
+}
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "negative cycle through nodes {:?}", self.nodes)
+    }
+}
+
+impl Error for NegativeCycle {}
+
+impl NegativeCycle {
+    /// Recovering the cycle from predecessors, where `node` was reached by a negative cycle.
+    ///
+    /// Going back as many steps as there are nodes is bound to end up on the cycle, if there is
+    /// one. Returns `None` if the predecessors lead back to the source instead.
+    pub(crate) fn from_predecessors<W>(pred: &[Option<Edge<W>>], node: usize) -> Option<NegativeCycle>
+        where W: Copy
+    {
+        let mut node = node;
+        for _ in 0..pred.len() {
+            node = pred[node]?.node;
+        }
+
+        // `node` is on the cycle now, walk around it once
+        let mut nodes = vec![node];
+        let mut prev = pred[node]?.node;
+        while prev != node {
+            nodes.push(prev);
+            prev = pred[prev]?.node;
+        }
+        nodes.reverse();
+
+        Some(NegativeCycle { nodes })
+    }
+}
+
+
+/// Bellman-Ford shortest path algorithm, allowing negative edge costs.
+///
+/// Relaxes every edge in rounds until no distance changes anymore. Without negative cycles all
+/// shortest paths have less edges than there are nodes, so this takes at most `n - 1` rounds. If
+/// some distance still changes in round `n`, a negative cycle is reachable from `source`, and its
+/// nodes are returned as error. A distance getting too cheap to represent in `W` is taken as a
+/// sign of a negative cycle as well.
+///
+/// # Panics
+///
+/// If some shortest path is too cheap to represent in `W` without going around a cycle.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::bellman_ford::bellman_ford;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (0, 2, 1), (2, 1, -2)]);
+/// let tree = bellman_ford(&graph, 0).unwrap();
+/// assert_eq!(tree.distance_to(1), Some(-1));
+///
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, -3), (2, 1, 2)]);
+/// assert_eq!(bellman_ford(&graph, 0).unwrap_err().nodes, vec![1, 2]);
+/// ```
+pub fn bellman_ford<W, G>(graph: &G, source: usize) -> Result<ShortestPathTree<W>, NegativeCycle>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let n = graph.node_count();
+    // dist[node] = current shortest distance from `source` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; n];
+    // pred[node] = edge back to the node we reached `node` from
+    let mut pred: Vec<Option<Edge<W>>> = vec![None; n];
+
+    dist[source] = Some(W::zero());
+
+    for round in 1..=n {
+        // Some node whose distance changed in this round
+        let mut changed = None;
+
+        for position in 0..n {
+            let cost = match dist[position] {
+                Some(cost) => cost,
+                None => continue,
+            };
+
+            for edge in graph.neighbors(position) {
+                let next = match cost.checked_add(edge.cost) {
+                    Some(next) => next,
+                    None if edge.cost < W::zero() => return Err(too_cheap(&mut pred, position, edge)),
+                    // Too expensive to even represent
+                    None => continue,
+                };
+
+                if dist[edge.node].is_none_or(|d| next < d) {
+                    // Relaxation, we have now found a better way
+                    dist[edge.node] = Some(next);
+                    pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
+                    changed = Some(edge.node);
+                }
+            }
+        }
+
+        match changed {
+            None => break,
+            Some(node) if round == n => {
+                let cycle = NegativeCycle::from_predecessors(&pred, node);
+                return Err(cycle.expect("changes in round n are caused by a negative cycle"));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(ShortestPathTree::from_predecessors(source, dist, pred))
+}
+
+
+/// Shortest Path Faster Algorithm, a queue-based variant of [`bellman_ford`].
+///
+/// Instead of relaxing every edge in every round, only the edges of nodes whose distance changed
+/// are relaxed, keeping these nodes in a [`BoundedFIFO`]. Without negative cycles every node is
+/// queued less than `n` times, so queueing a node for the `n`th time proves a negative cycle.
+///
+/// # Panics
+///
+/// If some shortest path is too cheap to represent in `W` without going around a cycle, like
+/// [`bellman_ford`].
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::bellman_ford::spfa;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (0, 2, 1), (2, 1, -2)]);
+/// assert_eq!(spfa(&graph, 0).unwrap().distance_to(1), Some(-1));
+/// ```
+///
+/// [`bellman_ford`]: fn.bellman_ford.html
+/// [`BoundedFIFO`]: ../fifo/struct.BoundedFIFO.html
+pub fn spfa<W, G>(graph: &G, source: usize) -> Result<ShortestPathTree<W>, NegativeCycle>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let n = graph.node_count();
+    // dist[node] = current shortest distance from `source` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; n];
+    // pred[node] = edge back to the node we reached `node` from
+    let mut pred: Vec<Option<Edge<W>>> = vec![None; n];
+    // queued[node] = whether `node` is waiting in the queue already
+    let mut queued = vec![false; n];
+    // count[node] = how often `node` has been queued again
+    let mut count = vec![0; n];
+
+    let mut queue = BoundedFIFO::new(n);
+
+    dist[source] = Some(W::zero());
+    queue.push_back(source);
+    queued[source] = true;
+
+    while let Some(position) = queue.pop_front() {
+        queued[position] = false;
+        let cost = dist[position].expect("queued nodes have a distance");
+
+        for edge in graph.neighbors(position) {
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                None if edge.cost < W::zero() => return Err(too_cheap(&mut pred, position, edge)),
+                // Too expensive to even represent
+                None => continue,
+            };
+
+            if dist[edge.node].is_none_or(|d| next < d) {
+                // Relaxation, we have now found a better way
+                dist[edge.node] = Some(next);
+                pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
+
+                if !queued[edge.node] {
+                    count[edge.node] += 1;
+                    if count[edge.node] >= n {
+                        // The predecessors usually run into the cycle, otherwise take the long way
+                        return Err(match NegativeCycle::from_predecessors(&pred, edge.node) {
+                            Some(cycle) => cycle,
+                            None => bellman_ford(graph, source)
+                                .expect_err("queueing a node n times is caused by a negative cycle"),
+                        });
+                    }
+                    queue.push_back(edge.node);
+                    queued[edge.node] = true;
+                }
+            }
+        }
+    }
+
+    Ok(ShortestPathTree::from_predecessors(source, dist, pred))
+}
+
+
+/// Negative cycle for relaxing `edge` from `position` to a distance too cheap to represent.
+///
+/// The relaxation still counts, so the predecessors lead around the cycle that made the distance
+/// this cheap.
+fn too_cheap<W: Weight>(pred: &mut [Option<Edge<W>>], position: usize, edge: Edge<W>) -> NegativeCycle {
+    pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
+    NegativeCycle::from_predecessors(pred, edge.node)
+        .expect("shortest path costs are representable")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AdjacencyList;
+    use crate::dijkstra::dijkstra_all;
+    use crate::testutil::{random_graph, XorShift};
+
+    // Random graph with costs in `-shift..=max_cost - shift`.
+    fn random_signed_graph(rng: &mut XorShift, max_nodes: usize, max_edges: usize, max_cost: usize, shift: i64) -> AdjacencyList<i64> {
+        let graph = random_graph(rng, max_nodes, max_edges, max_cost);
+        let mut signed = AdjacencyList::with_capacity(graph.node_count());
+        for _ in 0..graph.node_count() {
+            signed.add_node();
+        }
+        for from in 0..graph.node_count() {
+            for edge in graph.neighbors(from) {
+                signed.add_edge(from, edge.node, edge.cost as i64 - shift);
+            }
+        }
+        signed
+    }
+
+    // Check that `cycle` is a cycle in `graph` with negative cost.
+    fn assert_negative_cycle(graph: &AdjacencyList<i64>, cycle: &NegativeCycle) {
+        let nodes = &cycle.nodes;
+        assert!(!nodes.is_empty());
+        let mut total = 0;
+        for (i, &from) in nodes.iter().enumerate() {
+            let to = nodes[(i + 1) % nodes.len()];
+            total += graph.neighbors(from).iter()
+                .filter(|edge| edge.node == to)
+                .map(|edge| edge.cost)
+                .min()
+                .expect("consecutive cycle nodes are connected");
+        }
+        assert!(total < 0);
+    }
+
+    #[test]
+    fn negative_edges() {
+        //          4        -2
+        //      0 -----> 1 <----- 2
+        //      |                 ^
+        //      +-----------------+
+        //               1
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (0, 2, 1), (2, 1, -2)]);
+        for tree in [bellman_ford(&graph, 0).unwrap(), spfa(&graph, 0).unwrap()] {
+            assert_eq!(tree.distance_to(0), Some(0));
+            assert_eq!(tree.distance_to(1), Some(-1));
+            assert_eq!(tree.distance_to(2), Some(1));
+            assert_eq!(tree.path_to(1).unwrap().nodes, vec![0, 2, 1]);
+        }
+    }
+
+    #[test]
+    fn unreachable_cycle() {
+        // The negative cycle between 2 and 3 can not be reached from 0
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (2, 3, -1), (3, 2, -1)]);
+        assert_eq!(bellman_ford(&graph, 0).unwrap().distance_to(2), None);
+        assert_eq!(spfa(&graph, 0).unwrap().distance_to(2), None);
+        assert!(bellman_ford(&graph, 2).is_err());
+        assert!(spfa(&graph, 2).is_err());
+    }
+
+    #[test]
+    fn self_loop() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 1, -1)]);
+        assert_eq!(bellman_ford(&graph, 0), Err(NegativeCycle { nodes: vec![1] }));
+        assert_eq!(spfa(&graph, 0), Err(NegativeCycle { nodes: vec![1] }));
+    }
+
+    #[test]
+    fn underflow() {
+        // Going around the cycle once costs -50, which soon is too cheap for `i8`
+        let graph = AdjacencyList::<i8>::from_edges(vec![(0, 1, -100), (1, 0, 50)]);
+        assert_eq!(bellman_ford(&graph, 0), Err(NegativeCycle { nodes: vec![0, 1] }));
+        assert_eq!(spfa(&graph, 0), Err(NegativeCycle { nodes: vec![0, 1] }));
+    }
+
+    #[test]
+    fn like_dijkstra() {
+        let mut rng = XorShift::new(1234);
+        for _ in 0..500 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let source = rng.below(graph.node_count());
+            let tree = dijkstra_all(&graph, source);
+            let bf = bellman_ford(&graph, source).unwrap();
+            let queue = spfa(&graph, source).unwrap();
+            for node in 0..graph.node_count() {
+                assert_eq!(bf.distance_to(node), tree.distance_to(node));
+                assert_eq!(queue.distance_to(node), tree.distance_to(node));
+            }
+        }
+    }
+
+    #[test]
+    fn random_negative() {
+        let mut rng = XorShift::new(4321);
+        let mut cycles = 0;
+        for _ in 0..2000 {
+            let graph = random_signed_graph(&mut rng, 10, 25, 20, 4);
+            let source = rng.below(graph.node_count());
+            match (bellman_ford(&graph, source), spfa(&graph, source)) {
+                (Ok(bf), Ok(queue)) => {
+                    for node in 0..graph.node_count() {
+                        assert_eq!(bf.distance_to(node), queue.distance_to(node));
+                        if let Some(path) = bf.path_to(node) {
+                            assert_eq!(path.edges.iter().map(|e| e.cost).sum::<i64>(), path.cost);
+                        }
+                    }
+                }
+                (Err(bf), Err(queue)) => {
+                    cycles += 1;
+                    assert_negative_cycle(&graph, &bf);
+                    assert_negative_cycle(&graph, &queue);
+                }
+                (bf, queue) => panic!("disagreement: {:?} and {:?}", bf, queue),
+            }
+        }
+        // Make sure both cases are actually tested
+        assert!(cycles > 100 && cycles < 1900);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::csr::CsrGraph;
+use crate::dijkstra::ShortestPathTree;
+use crate::fifo::BoundedFIFO;
+use crate::graph::{Edge, Graph};
+
+
+/// Breadth-first search, finding paths with the least number of edges.
+///
+/// Edge costs are ignored, every edge counts as one step. Nodes are taken from the front of a
+/// [`BoundedFIFO`] and their unvisited neighbors are added to the back, so nodes are visited in
+/// order of their number of steps from `source`. The resulting paths report one step per edge.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::bfs::bfs;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (1, 2, 5), (0, 2, 20)]);
+/// let tree = bfs(&graph, 0);
+/// assert_eq!(tree.distance_to(2), Some(1));
+/// assert_eq!(tree.path_to(2).unwrap().nodes, vec![0, 2]);
+/// ```
+///
+/// [`BoundedFIFO`]: ../fifo/struct.BoundedFIFO.html
+pub fn bfs<G: Graph>(graph: &G, source: usize) -> ShortestPathTree<usize> {
+    // dist[node] = number of steps from `source` to `node`
+    let mut dist: Vec<Option<usize>> = vec![None; graph.node_count()];
+    // pred[node] = step back to the node we reached `node` from
+    let mut pred: Vec<Option<Edge<usize>>> = vec![None; graph.node_count()];
+
+    let mut queue = BoundedFIFO::new(graph.node_count());
+
+    dist[source] = Some(0);
+    queue.push_back(source);
+
+    while let Some(position) = queue.pop_front() {
+        let steps = dist[position].unwrap() + 1;
+        for edge in graph.neighbors(position) {
+            // The first time a node is found is the shortest way there
+            if dist[edge.node].is_none() {
+                dist[edge.node] = Some(steps);
+                pred[edge.node] = Some(Edge { node: position, cost: 1 });
+                queue.push_back(edge.node);
+            }
+        }
+    }
+
+    ShortestPathTree::from_predecessors(source, dist, pred)
+}
+
+
+/// Nodes reachable from `source`, grouped by their number of steps from it.
+///
+/// `layers[i]` contains all nodes whose shortest path has `i` edges, in the order they are
+/// visited by breadth-first search.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::bfs::bfs_layers;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (0, 2, 1), (1, 3, 1), (2, 3, 1)]);
+/// assert_eq!(bfs_layers(&graph, 0), vec![vec![0], vec![1, 2], vec![3]]);
+/// ```
+pub fn bfs_layers<G: Graph>(graph: &G, source: usize) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; graph.node_count()];
+    let mut layers = Vec::new();
+
+    let mut queue = BoundedFIFO::new(graph.node_count());
+
+    visited[source] = true;
+    queue.push_back(source);
+
+    // Everything in the queue belongs to the same layer at this point
+    while !queue.is_empty() {
+        let mut layer = Vec::with_capacity(queue.size());
+        for _ in 0..queue.size() {
+            let position = queue.pop_front().unwrap();
+            layer.push(position);
+            for edge in graph.neighbors(position) {
+                if !visited[edge.node] {
+                    visited[edge.node] = true;
+                    queue.push_back(edge.node);
+                }
+            }
+        }
+        layers.push(layer);
+    }
+
+    layers
+}
+
+
+/// Labelling of the connected components of a graph, see [`connected_components`].
+///
+/// [`connected_components`]: fn.connected_components.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    /// Number of components
+    pub count: usize,
+    /// labels[node] = component of `node`, in `0..count`
+    pub labels: Vec<usize>,
+}
+
+
+/// Weakly connected components, ignoring the direction of edges.
+///
+/// A breadth-first search is started from each node not labelled yet, following edges in both
+/// directions, and labels everything it reaches. Components are numbered in order of their
+/// smallest node.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::bfs::connected_components;
+/// let mut graph = AdjacencyList::from_edges(vec![(0, 1, 1), (2, 1, 1), (3, 4, 1)]);
+/// graph.add_node();
+/// let components = connected_components(&graph);
+/// assert_eq!(components.count, 3);
+/// assert_eq!(components.labels, vec![0, 0, 0, 1, 1, 2]);
+/// ```
+pub fn connected_components<G: Graph>(graph: &G) -> Components {
+    let inverted = CsrGraph::transpose_of(graph);
+    let mut labels: Vec<Option<usize>> = vec![None; graph.node_count()];
+    let mut count = 0;
+
+    let mut queue = BoundedFIFO::new(graph.node_count());
+
+    for source in 0..graph.node_count() {
+        if labels[source].is_some() {
+            continue;
+        }
+
+        labels[source] = Some(count);
+        queue.push_back(source);
+
+        while let Some(position) = queue.pop_front() {
+            let edges = graph.neighbors(position).chain(inverted.neighbors(position));
+            for edge in edges {
+                if labels[edge.node].is_none() {
+                    labels[edge.node] = Some(count);
+                    queue.push_back(edge.node);
+                }
+            }
+        }
+
+        count += 1;
+    }
+
+    Components {
+        count,
+        labels: labels.into_iter().map(Option::unwrap).collect(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AdjacencyList;
+    use crate::dijkstra::dijkstra_all;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn like_dijkstra() {
+        let mut rng = XorShift::new(8);
+        for _ in 0..500 {
+            // With all costs 1, Dijkstra counts steps as well
+            let graph = random_graph(&mut rng, 20, 40, 0);
+            let graph = AdjacencyList::from_edges((0..graph.node_count())
+                .flat_map(|from| graph.neighbors(from).iter().map(move |edge| (from, edge.node, 1))));
+            if graph.node_count() == 0 {
+                continue;
+            }
+            let source = rng.below(graph.node_count());
+
+            let tree = bfs(&graph, source);
+            let expected = dijkstra_all(&graph, source);
+            for node in 0..graph.node_count() {
+                assert_eq!(tree.distance_to(node), expected.distance_to(node));
+                if let Some(path) = tree.path_to(node) {
+                    assert_eq!(path.edges.len(), path.cost);
+                }
+            }
+
+            let layers = bfs_layers(&graph, source);
+            for (steps, layer) in layers.iter().enumerate() {
+                for &node in layer {
+                    assert_eq!(tree.distance_to(node), Some(steps));
+                }
+            }
+            assert_eq!(layers.iter().map(Vec::len).sum::<usize>(), tree.settled().count());
+        }
+    }
+
+    #[test]
+    fn components() {
+        let mut rng = XorShift::new(10);
+        for _ in 0..500 {
+            let graph = random_graph(&mut rng, 20, 15, 5);
+            let components = connected_components(&graph);
+            let labels = &components.labels;
+
+            // Edges stay within components
+            for from in 0..graph.node_count() {
+                for edge in graph.neighbors(from) {
+                    assert_eq!(labels[from], labels[edge.node]);
+                }
+            }
+            // Components are numbered by their smallest node
+            let mut next = 0;
+            for &label in labels {
+                assert!(label <= next);
+                if label == next {
+                    next += 1;
+                }
+            }
+            assert_eq!(next, components.count);
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::BinaryHeap;
+
+use crate::dijkstra::{Frontier, ShortestPath, State};
+use crate::graph::{Edge, Graph};
+use crate::indexed_heap::IndexedHeap;
+use crate::weight::Weight;
+
+
+/// Number of nodes a witness search may settle before giving up. A witness missed only costs an
+/// unnecessary shortcut.
+const WITNESS_SETTLE_LIMIT: usize = 100;
+
+
+/// Edge of a [`ContractionHierarchy`], either of the graph or a shortcut.
+///
+/// [`ContractionHierarchy`]: struct.ContractionHierarchy.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Link<W> {
+    /// Node at the other end
+    node: usize,
+    cost: W,
+    /// Node a shortcut skips, which is lower in the hierarchy than both ends; `None` for edges
+    /// of the graph
+    middle: Option<usize>,
+}
+
+
+/// Contraction hierarchy of a graph, answering shortest path queries by exploring a tiny part
+/// of it.
+///
+/// Nodes are contracted one by one, by a rough order of importance. Contracting a node `v`
+/// removes it from the graph, adding a shortcut `u -> w` for each pair of edges `u -> v -> w`
+/// unless a witness search finds a path from `u` to `w` at most as expensive without `v`.
+/// Shortest paths then stay intact among the remaining nodes.
+///
+/// Every node ends up with a rank, its position in the order. A shortest path of the graph with
+/// all shortcuts first goes up in rank and then down, so a query only searches upwards from
+/// `start` and, against the edges, from `goal`. The path found is unpacked by replacing each
+/// shortcut by the two edges it skips.
+///
+/// Nodes are ordered by edge difference: the number of shortcuts contracting a node would add,
+/// minus the number of edges it removes, plus the number of neighbors contracted already, so
+/// contraction spreads evenly over the graph. Priorities are updated lazily, when a node comes up
+/// for contraction, and for the neighbors of every node contracted.
+///
+/// Edge costs must not be negative.
+///
+/// # Example
+/// ```
+/// use vasa::ch::ContractionHierarchy;
+/// use vasa::graph::AdjacencyList;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (2, 3, 1), (0, 3, 9)]);
+/// let ch = ContractionHierarchy::new(&graph);
+/// assert_eq!(ch.distance(0, 3), Some(4));
+/// assert_eq!(ch.path(0, 3).unwrap().nodes, vec![0, 1, 2, 3]);
+/// assert_eq!(ch.distance(3, 0), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractionHierarchy<W = usize> {
+    /// rank[node] = position of `node` in the contraction order
+    rank: Vec<usize>,
+    /// up[node] = edges leaving `node` towards nodes of higher rank
+    up: Vec<Vec<Link<W>>>,
+    /// down[node] = edges into `node` from nodes of higher rank, pointing back to those
+    down: Vec<Vec<Link<W>>>,
+}
+
+
+/// Graph of the nodes not contracted yet, with at most one edge per pair of nodes.
+struct Remaining<W> {
+    out: Vec<Vec<Link<W>>>,
+    inc: Vec<Vec<Link<W>>>,
+    contracted: Vec<bool>,
+    /// contracted_neighbors[node] = number of neighbors of `node` contracted already
+    contracted_neighbors: Vec<usize>,
+    /// Distances of the witness search, and the nodes whose distance it set
+    dist: Vec<Option<W>>,
+    touched: Vec<usize>,
+}
+
+impl<W: Weight> Remaining<W> {
+    fn new<G>(graph: &G) -> Remaining<W>
+        where G: Graph<Weight = W>
+    {
+        let n = graph.node_count();
+        let mut remaining = Remaining {
+            out: vec![Vec::new(); n],
+            inc: vec![Vec::new(); n],
+            contracted: vec![false; n],
+            contracted_neighbors: vec![0; n],
+            dist: vec![None; n],
+            touched: Vec::new(),
+        };
+        for from in 0..n {
+            for Edge { node, cost } in graph.neighbors(from) {
+                debug_assert!(cost >= W::zero(), "negative edge cost {:?}", cost);
+                // Self-loops are never part of shortest paths
+                if node != from {
+                    remaining.add_edge(from, Link { node, cost, middle: None });
+                }
+            }
+        }
+        remaining
+    }
+
+    /// Adding an edge from `from`, unless there is one at most as expensive already.
+    fn add_edge(&mut self, from: usize, link: Link<W>) {
+        let back = Link { node: from, ..link };
+        match self.out[from].iter().position(|l| l.node == link.node) {
+            Some(i) if self.out[from][i].cost <= link.cost => {}
+            Some(i) => {
+                self.out[from][i] = link;
+                let j = self.inc[link.node].iter().position(|l| l.node == from).unwrap();
+                self.inc[link.node][j] = back;
+            }
+            None => {
+                self.out[from].push(link);
+                self.inc[link.node].push(back);
+            }
+        }
+    }
+
+    /// Shortcuts `(from, link)` needed to contract `node`.
+    fn shortcuts(&mut self, node: usize) -> Vec<(usize, Link<W>)> {
+        let mut shortcuts = Vec::new();
+        for i in 0..self.inc[node].len() {
+            let Link { node: from, cost: to_node, .. } = self.inc[node][i];
+            let limit = self.out[node].iter()
+                .filter_map(|link| to_node.checked_add(link.cost))
+                .max();
+            let limit = match limit {
+                Some(limit) => limit,
+                None => continue,
+            };
+            self.witness_search(from, node, limit);
+
+            for link in &self.out[node] {
+                let via = match to_node.checked_add(link.cost) {
+                    Some(via) => via,
+                    None => continue,
+                };
+                if link.node != from && self.dist[link.node].is_none_or(|d| d > via) {
+                    shortcuts.push((from, Link { node: link.node, cost: via, middle: Some(node) }));
+                }
+            }
+        }
+        shortcuts
+    }
+
+    /// Dijkstra's algorithm from `source` among the remaining nodes except `skip`, up to cost
+    /// `limit` or until `WITNESS_SETTLE_LIMIT` nodes are settled.
+    fn witness_search(&mut self, source: usize, skip: usize, limit: W) {
+        for node in self.touched.drain(..) {
+            self.dist[node] = None;
+        }
+        let mut heap: BinaryHeap<State<W>> = BinaryHeap::new();
+        self.dist[source] = Some(W::zero());
+        self.touched.push(source);
+        Frontier::push(&mut heap, W::zero(), source);
+
+        let mut settled = 0;
+        while let Some((cost, node)) = heap.pop_min() {
+            if Some(cost) > self.dist[node] { continue; }
+            if cost > limit || settled == WITNESS_SETTLE_LIMIT {
+                break;
+            }
+            settled += 1;
+
+            for link in &self.out[node] {
+                if link.node == skip {
+                    continue;
+                }
+                let next = match cost.checked_add(link.cost) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                if self.dist[link.node].is_none_or(|d| next < d) {
+                    if self.dist[link.node].is_none() {
+                        self.touched.push(link.node);
+                    }
+                    self.dist[link.node] = Some(next);
+                    Frontier::push(&mut heap, next, link.node);
+                }
+            }
+        }
+    }
+
+    /// Edge difference of contracting `node`, plus its contracted neighbors.
+    fn priority(&mut self, node: usize) -> isize {
+        let added = self.shortcuts(node).len() as isize;
+        let removed = (self.inc[node].len() + self.out[node].len()) as isize;
+        added - removed + self.contracted_neighbors[node] as isize
+    }
+
+    /// Removing `node` and adding the shortcuts needed, returning its edges to the remaining
+    /// nodes as `(out, inc)`.
+    fn contract(&mut self, node: usize) -> (Vec<Link<W>>, Vec<Link<W>>) {
+        let shortcuts = self.shortcuts(node);
+        let out = std::mem::take(&mut self.out[node]);
+        let inc = std::mem::take(&mut self.inc[node]);
+        for link in &out {
+            self.inc[link.node].retain(|l| l.node != node);
+        }
+        for link in &inc {
+            self.out[link.node].retain(|l| l.node != node);
+        }
+        for (from, link) in shortcuts {
+            self.add_edge(from, link);
+        }
+        self.contracted[node] = true;
+        (out, inc)
+    }
+}
+
+
+impl<W: Weight> ContractionHierarchy<W> {
+    /// Preprocessing `graph` into a contraction hierarchy.
+    pub fn new<G>(graph: &G) -> ContractionHierarchy<W>
+        where G: Graph<Weight = W>
+    {
+        let n = graph.node_count();
+        let mut remaining = Remaining::new(graph);
+        let mut queue = IndexedHeap::new(n);
+        for node in 0..n {
+            let priority = remaining.priority(node);
+            queue.push(node, priority);
+        }
+
+        let mut ch = ContractionHierarchy { rank: vec![0; n], up: vec![Vec::new(); n], down: vec![Vec::new(); n] };
+        let mut next_rank = 0;
+        while let Some((node, _)) = queue.pop() {
+            // Lazy update: contract later if the priority got worse than the next one's
+            let priority = remaining.priority(node);
+            if queue.peek().is_some_and(|(_, next)| priority > next) {
+                queue.push(node, priority);
+                continue;
+            }
+
+            let (out, inc) = remaining.contract(node);
+            ch.rank[node] = next_rank;
+            next_rank += 1;
+
+            let mut neighbors: Vec<_> = out.iter().chain(&inc).map(|link| link.node).collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            for neighbor in neighbors {
+                remaining.contracted_neighbors[neighbor] += 1;
+                // Only lowered here, raised lazily when popped
+                let priority = remaining.priority(neighbor);
+                queue.push_or_decrease(neighbor, priority);
+            }
+            ch.up[node] = out;
+            ch.down[node] = inc;
+        }
+        debug_assert!(remaining.contracted.iter().all(|&c| c));
+
+        ch
+    }
+
+    /// Return number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.rank.len()
+    }
+
+    /// Position of `node` in the contraction order, from 0 for the first node contracted.
+    pub fn rank(&self, node: usize) -> usize {
+        self.rank[node]
+    }
+
+    /// Return number of shortcuts added.
+    pub fn shortcut_count(&self) -> usize {
+        self.up.iter().chain(&self.down).flatten().filter(|link| link.middle.is_some()).count()
+    }
+
+    /// Cost of the shortest path from `start` to `goal`, `None` if there is no path.
+    pub fn distance(&self, start: usize, goal: usize) -> Option<W> {
+        self.query(start, goal).map(|(cost, _, _, _)| cost)
+    }
+
+    /// Shortest path from `start` to `goal` in the graph, with all shortcuts unpacked.
+    pub fn path(&self, start: usize, goal: usize) -> Option<ShortestPath<W>> {
+        let (cost, meet, pred_f, pred_b) = self.query(start, goal)?;
+        let hierarchy = ShortestPath::stitch(cost, meet, &pred_f, &pred_b);
+
+        let mut nodes = vec![start];
+        let mut edges = Vec::new();
+        for (&from, edge) in hierarchy.nodes.iter().zip(&hierarchy.edges) {
+            self.unpack(from, edge.node, &mut nodes, &mut edges);
+        }
+        Some(ShortestPath { cost, nodes, edges })
+    }
+
+    /// Link of the hierarchy from `from` to `to`, which is stored at the lower of both.
+    fn link(&self, from: usize, to: usize) -> Link<W> {
+        let (links, other) = if self.rank[from] < self.rank[to] {
+            (&self.up[from], to)
+        } else {
+            (&self.down[to], from)
+        };
+        *links.iter().find(|link| link.node == other).expect("link of the hierarchy")
+    }
+
+    /// Appending the edges of the graph that the link from `from` to `to` stands for.
+    fn unpack(&self, from: usize, to: usize, nodes: &mut Vec<usize>, edges: &mut Vec<Edge<W>>) {
+        // Links still to unpack, the next one last
+        let mut stack = vec![(from, to)];
+        while let Some((from, to)) = stack.pop() {
+            let link = self.link(from, to);
+            match link.middle {
+                Some(middle) => {
+                    stack.push((middle, to));
+                    stack.push((from, middle));
+                }
+                None => {
+                    nodes.push(to);
+                    edges.push(Edge { node: to, cost: link.cost });
+                }
+            }
+        }
+    }
+
+    /// Bidirectional upward search, returning the cost, the node where the searches meet, and the
+    /// predecessors of both searches.
+    ///
+    /// Unlike in [`dijkstra_bidir`], the searches run on different graphs, so the sum of both
+    /// frontiers is no bound on the paths left. Instead each search stops once its frontier is
+    /// at least as expensive as the best path found.
+    ///
+    /// [`dijkstra_bidir`]: ../dijkstra/fn.dijkstra_bidir.html
+    #[allow(clippy::type_complexity)]
+    fn query(&self, start: usize, goal: usize) -> Option<(W, usize, Vec<Option<Edge<W>>>, Vec<Option<Edge<W>>>)> {
+        let n = self.node_count();
+        let mut dist = [vec![None; n], vec![None; n]];
+        let mut pred = [vec![None; n], vec![None; n]];
+        let mut heaps: [BinaryHeap<State<W>>; 2] = [BinaryHeap::new(), BinaryHeap::new()];
+        let links = [&self.up, &self.down];
+
+        dist[0][start] = Some(W::zero());
+        dist[1][goal] = Some(W::zero());
+        Frontier::push(&mut heaps[0], W::zero(), start);
+        Frontier::push(&mut heaps[1], W::zero(), goal);
+        let mut best: Option<(W, usize)> = if start == goal { Some((W::zero(), start)) } else { None };
+
+        loop {
+            // Advance the search with the cheaper frontier, unless it can not find anything better
+            let tops = [heaps[0].peek_min(), heaps[1].peek_min()];
+            let open = |top: Option<W>| top.is_some_and(|top| best.is_none_or(|(cost, _)| top < cost));
+            let side = match (open(tops[0]), open(tops[1])) {
+                (false, false) => break,
+                (true, false) => 0,
+                (false, true) => 1,
+                (true, true) => if tops[0] <= tops[1] { 0 } else { 1 },
+            };
+
+            let (cost, node) = heaps[side].pop_min().unwrap();
+            if Some(cost) > dist[side][node] { continue; }
+
+            for link in &links[side][node] {
+                let next = match cost.checked_add(link.cost) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                if dist[side][link.node].is_none_or(|d| next < d) {
+                    dist[side][link.node] = Some(next);
+                    pred[side][link.node] = Some(Edge { node, cost: link.cost });
+                    Frontier::push(&mut heaps[side], next, link.node);
+
+                    let total = dist[1 - side][link.node].and_then(|other| next.checked_add(other));
+                    if let Some(total) = total {
+                        if best.is_none_or(|(cost, _)| total < cost) {
+                            best = Some((total, link.node));
+                        }
+                    }
+                }
+            }
+        }
+
+        let (cost, meet) = best?;
+        let [pred_f, pred_b] = pred;
+        Some((cost, meet, pred_f, pred_b))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra;
+    use crate::graph::AdjacencyList;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn like_dijkstra() {
+        let mut rng = XorShift::new(21);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 30, 100, 10);
+            let ch = ContractionHierarchy::new(&graph);
+            let n = graph.node_count();
+            let mut ranks: Vec<_> = (0..n).map(|node| ch.rank(node)).collect();
+            ranks.sort();
+            assert_eq!(ranks, (0..n).collect::<Vec<_>>());
+
+            for start in 0..n {
+                for goal in 0..n {
+                    let expected = dijkstra(&graph, start, goal);
+                    assert_eq!(ch.distance(start, goal), expected, "{} -> {}", start, goal);
+
+                    let path = match ch.path(start, goal) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    assert_eq!(path.nodes.first(), Some(&start));
+                    assert_eq!(path.nodes.last(), Some(&goal));
+                    assert_eq!(path.edges.len() + 1, path.nodes.len());
+                    assert_eq!(path.edges.iter().map(|edge| edge.cost).sum::<usize>(), path.cost);
+                    for (i, edge) in path.edges.iter().enumerate() {
+                        assert_eq!(edge.node, path.nodes[i + 1]);
+                        assert!(graph.neighbors(path.nodes[i]).contains(edge));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shortcuts() {
+        // Contracting the middle of a line needs a shortcut, unless there is a cheaper detour
+        let line = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1), (2, 1, 1), (1, 0, 1)]);
+        let ch = ContractionHierarchy::new(&line);
+        assert_eq!(ch.shortcut_count(), if ch.rank(1) == 0 { 2 } else { 0 });
+
+        let detour = AdjacencyList::from_edges(vec![(0, 1, 5), (1, 2, 5), (0, 3, 1), (3, 2, 1)]);
+        let ch = ContractionHierarchy::new(&detour);
+        assert_eq!(ch.shortcut_count(), 0);
+        assert_eq!(ch.path(0, 2).unwrap().nodes, vec![0, 3, 2]);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::graph::{AdjacencyList, Edge, Graph};
+
+
+/// Directed graph in compressed sparse row format.
+///
+/// All edges are stored in two flat arrays, sorted by the node they leave from: `targets` holds
+/// the nodes they point to and `weights` their costs. The edges of `node` are the ones in
+/// `offsets[node]..offsets[node + 1]`. Compared to an adjacency list of one `Vec` per node, this
+/// takes three allocations in total, and searches read the edges of consecutive nodes from
+/// consecutive memory.
+///
+/// The graph can not be changed once built.
+///
+/// # Example
+/// ```
+/// use vasa::csr::CsrGraph;
+/// use vasa::graph::Edge;
+/// let graph = CsrGraph::from_edges(vec![(1, 2, 5), (0, 1, 3), (0, 2, 9)]);
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(graph.edge_count(), 3);
+/// let edges: Vec<_> = graph.neighbors(0).collect();
+/// assert_eq!(edges, vec![Edge { node: 1, cost: 3 }, Edge { node: 2, cost: 9 }]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph<W = usize> {
+    /// offsets[node] = index of the first edge of `node`, with `offsets[n]` the number of edges
+    offsets: Vec<usize>,
+    /// targets[i] = node edge `i` points to
+    targets: Vec<usize>,
+    /// weights[i] = cost of edge `i`
+    weights: Vec<W>,
+}
+
+impl<W: Copy> From<&AdjacencyList<W>> for CsrGraph<W> {
+    fn from(graph: &AdjacencyList<W>) -> CsrGraph<W> {
+        CsrGraph::from_graph(graph)
+    }
+}
+
+impl<W> Default for CsrGraph<W> {
+    fn default() -> CsrGraph<W> {
+        CsrGraph { offsets: vec![0], targets: Vec::new(), weights: Vec::new() }
+    }
+}
+
+impl<W: Copy> CsrGraph<W> {
+    /// Building a `CsrGraph` from `(from, to, cost)` triples.
+    ///
+    /// The graph gets as many nodes as needed for the largest node mentioned. The edges of each
+    /// node keep their order, as in [`AdjacencyList::from_edges`].
+    ///
+    /// [`AdjacencyList::from_edges`]: ../graph/struct.AdjacencyList.html#method.from_edges
+    pub fn from_edges<I>(edges: I) -> CsrGraph<W>
+        where I: IntoIterator<Item = (usize, usize, W)>
+    {
+        let edges: Vec<_> = edges.into_iter().collect();
+        let n = edges.iter().map(|&(from, to, _)| from.max(to) + 1).max().unwrap_or(0);
+        CsrGraph::sorted(n, &edges)
+    }
+
+    /// Copying any graph into a `CsrGraph`.
+    pub fn from_graph<G>(graph: &G) -> CsrGraph<W>
+        where G: Graph<Weight = W>
+    {
+        let mut offsets = Vec::with_capacity(graph.node_count() + 1);
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        for node in 0..graph.node_count() {
+            for Edge { node, cost } in graph.neighbors(node) {
+                targets.push(node);
+                weights.push(cost);
+            }
+            offsets.push(targets.len());
+        }
+        CsrGraph { offsets, targets, weights }
+    }
+
+    /// Transpose of any graph, with the direction of every edge reversed.
+    ///
+    /// Takes `O(n + m)` time: edges are counted per target node first, which gives the offsets,
+    /// then placed in a second pass. The reversed edges of each node are in the order of the
+    /// nodes they came from.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::csr::CsrGraph;
+    /// use vasa::graph::{AdjacencyList, Edge};
+    /// let graph = AdjacencyList::from_edges(vec![(0, 2, 1), (1, 2, 4)]);
+    /// let transpose = CsrGraph::transpose_of(&graph);
+    /// let edges: Vec<_> = transpose.neighbors(2).collect();
+    /// assert_eq!(edges, vec![Edge { node: 0, cost: 1 }, Edge { node: 1, cost: 4 }]);
+    /// assert_eq!(transpose.neighbors(0).count(), 0);
+    /// ```
+    pub fn transpose_of<G>(graph: &G) -> CsrGraph<W>
+        where G: Graph<Weight = W>
+    {
+        let edges: Vec<_> = (0..graph.node_count())
+            .flat_map(|from| graph.neighbors(from).map(move |Edge { node, cost }| (node, from, cost)))
+            .collect();
+        CsrGraph::sorted(graph.node_count(), &edges)
+    }
+
+    /// Transpose of this graph, see [`transpose_of`].
+    ///
+    /// [`transpose_of`]: #method.transpose_of
+    pub fn transpose(&self) -> CsrGraph<W> {
+        CsrGraph::transpose_of(self)
+    }
+
+    /// Sorting `(from, to, cost)` triples of a graph with `n` nodes by `from`, keeping the order
+    /// of edges with the same `from` (counting sort).
+    fn sorted(n: usize, edges: &[(usize, usize, W)]) -> CsrGraph<W> {
+        // offsets[node + 1] = number of edges of `node`, at first
+        let mut offsets = vec![0; n + 1];
+        for &(from, to, _) in edges {
+            assert!(from < n && to < n, "unknown edge {} -> {}", from, to);
+            offsets[from + 1] += 1;
+        }
+        for node in 0..n {
+            offsets[node + 1] += offsets[node];
+        }
+
+        // next[node] = index for the next edge of `node`
+        let mut next = offsets.clone();
+        let mut targets = vec![0; edges.len()];
+        let mut weights = Vec::with_capacity(edges.len());
+        // Every slot is written exactly once below, so any cost will do as placeholder
+        if let Some(&(_, _, cost)) = edges.first() {
+            weights.resize(edges.len(), cost);
+        }
+        for &(from, to, cost) in edges {
+            targets[next[from]] = to;
+            weights[next[from]] = cost;
+            next[from] += 1;
+        }
+
+        CsrGraph { offsets, targets, weights }
+    }
+
+    /// Return number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Return number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Iterate over the outgoing edges of `node`.
+    ///
+    /// # Panics
+    /// If the node does not exist.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<W>> + '_ {
+        let edges = self.offsets[node]..self.offsets[node + 1];
+        self.targets[edges.clone()].iter()
+            .zip(&self.weights[edges])
+            .map(|(&node, &cost)| Edge { node, cost })
+    }
+}
+
+impl<W: Copy> Graph for CsrGraph<W> {
+    type Weight = W;
+
+    fn node_count(&self) -> usize {
+        CsrGraph::node_count(self)
+    }
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<W>> {
+        CsrGraph::neighbors(self, node)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bellman_ford::spfa;
+    use crate::bfs::{bfs, connected_components};
+    use crate::dial::dial;
+    use crate::dijkstra::{dijkstra, dijkstra_all, dijkstra_bidir_path};
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn empty() {
+        let graph: CsrGraph = CsrGraph::from_edges(Vec::new());
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph, CsrGraph::default());
+        assert_eq!(graph.transpose(), graph);
+    }
+
+    #[test]
+    fn like_adjacency_list() {
+        let mut rng = XorShift::new(15);
+        for _ in 0..200 {
+            let graph = random_graph(&mut rng, 20, 60, 10);
+            let csr = CsrGraph::from(&graph);
+            assert_eq!(csr.node_count(), graph.node_count());
+            assert_eq!(csr.edge_count(), graph.edge_count());
+
+            let edges: Vec<_> = (0..graph.node_count())
+                .flat_map(|from| graph.neighbors(from).iter().map(move |edge| (from, edge.node, edge.cost)))
+                .collect();
+            let n = edges.iter().map(|&(from, to, _)| from.max(to) + 1).max().unwrap_or(0);
+            let from_edges = CsrGraph::from_edges(edges);
+            for node in 0..graph.node_count() {
+                assert!(csr.neighbors(node).eq(graph.neighbors(node).iter().copied()));
+                if node < n {
+                    assert!(from_edges.neighbors(node).eq(csr.neighbors(node)));
+                }
+            }
+
+            // Reversing twice restores the graph, up to the order of edges
+            let transpose = csr.transpose();
+            assert_eq!(transpose.edge_count(), csr.edge_count());
+            for node in 0..graph.node_count() {
+                for edge in transpose.neighbors(node) {
+                    assert!(graph.neighbors(edge.node).contains(&Edge { node, cost: edge.cost }));
+                }
+            }
+            let back = transpose.transpose();
+            for node in 0..graph.node_count() {
+                let mut expected: Vec<_> = csr.neighbors(node).map(|e| (e.node, e.cost)).collect();
+                let mut actual: Vec<_> = back.neighbors(node).map(|e| (e.node, e.cost)).collect();
+                expected.sort();
+                actual.sort();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn searches() {
+        let mut rng = XorShift::new(16);
+        for _ in 0..300 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let csr = CsrGraph::from(&graph);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            assert_eq!(dijkstra(&csr, start, goal), dijkstra(&graph, start, goal));
+            assert_eq!(dijkstra_bidir_path(&csr, start, goal), dijkstra_bidir_path(&graph, start, goal));
+            assert_eq!(dial(&csr, start, goal), dijkstra(&graph, start, goal));
+            assert_eq!(dijkstra_all(&csr, start), dijkstra_all(&graph, start));
+            assert_eq!(spfa(&csr, start), spfa(&graph, start));
+            assert_eq!(bfs(&csr, start), bfs(&graph, start));
+            assert_eq!(connected_components(&csr), connected_components(&graph));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_node() {
+        let graph: CsrGraph = CsrGraph::from_edges(vec![(0, 1, 1)]);
+        graph.neighbors(2).count();
+    }
+}
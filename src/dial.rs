@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+use std::convert::TryInto;
+
+use crate::dijkstra::dijkstra;
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+
+/// 0-1 BFS, shortest paths for graphs whose edges cost either zero or one.
+///
+/// Like breadth-first search, but nodes reached by a free edge are added to the front of a
+/// double-ended queue instead of the back. The queue then always holds nodes of at most two
+/// consecutive distances, in order, so nodes come out in the same order as from the min-heap of
+/// [`dijkstra`], without any comparisons.
+///
+/// This works for any single non-zero cost in place of one.
+///
+/// # Panics
+///
+/// If edges with two different non-zero costs are found.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dial::zero_one_bfs;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1), (0, 3, 0), (3, 2, 1)]);
+/// assert_eq!(zero_one_bfs(&graph, 0, 2), Some(1));
+/// assert_eq!(zero_one_bfs(&graph, 2, 0), None);
+/// ```
+///
+/// [`dijkstra`]: ../dijkstra/fn.dijkstra.html
+pub fn zero_one_bfs<W, G>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    // dist[node] = current shortest distance from `start` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; graph.node_count()];
+    // The only non-zero cost seen so far
+    let mut one: Option<W> = None;
+
+    let mut deque = VecDeque::new();
+
+    // We're at `start`, with a zero cost
+    dist[start] = Some(W::zero());
+    deque.push_back((W::zero(), start));
+
+    while let Some((cost, position)) = deque.pop_front() {
+        // Important as we may have already found a better way
+        if Some(cost) > dist[position] { continue; }
+
+        if position == goal { return Some(cost); }
+
+        for edge in graph.neighbors(position) {
+            let free = edge.cost == W::zero();
+            if !free {
+                assert!(one.is_none_or(|one| one == edge.cost),
+                        "0-1 BFS with costs {:?} and {:?}", one, edge.cost);
+                one = Some(edge.cost);
+            }
+
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                // Too expensive to even represent
+                None => continue,
+            };
+
+            if dist[edge.node].is_none_or(|d| next < d) {
+                // Relaxation, we have now found a better way
+                dist[edge.node] = Some(next);
+                if free {
+                    deque.push_front((next, edge.node));
+                } else {
+                    deque.push_back((next, edge.node));
+                }
+            }
+        }
+    }
+
+    // Goal not reachable
+    None
+}
+
+
+/// Dial's algorithm, Dijkstra's algorithm with a bucket queue for small integer costs.
+///
+/// Instead of a heap, nodes are kept in one bucket per distance. All distances in the queue lie
+/// between the current one and the current one plus the largest edge cost `C`, so `C + 1`
+/// buckets used as a ring suffice. Walking through the buckets in order takes `O(m + n C)` time
+/// in total, which beats a heap when `C` is small.
+///
+/// If walking the buckets, `n C`, would take longer than the `(n + m) log n` of a heap, this runs
+/// [`dijkstra`] instead.
+///
+/// Costs must not be negative.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dial::dial;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dial(&graph, 0, 2), Some(3));
+/// assert_eq!(dial(&graph, 2, 0), None);
+/// ```
+///
+/// [`dijkstra`]: ../dijkstra/fn.dijkstra.html
+pub fn dial<W, G>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight + TryInto<usize>,
+          G: Graph<Weight = W>
+{
+    let key = |cost: W| -> usize {
+        cost.try_into().ok().expect("costs must not be negative and fit into usize")
+    };
+
+    // Largest edge cost, deciding the number of buckets
+    let n = graph.node_count();
+    let mut edges = 0;
+    let mut max_cost = 0;
+    for edge in (0..n).flat_map(|node| graph.neighbors(node)) {
+        edges += 1;
+        max_cost = max_cost.max(key(edge.cost));
+    }
+    let log_n = (usize::BITS - n.leading_zeros()) as usize;
+    if n.saturating_mul(max_cost) > (n + edges).saturating_mul(log_n) {
+        return dijkstra(graph, start, goal);
+    }
+
+    // dist[node] = current shortest distance from `start` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; n];
+
+    // buckets[d % (max_cost + 1)] = nodes with distance `d`
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); max_cost + 1];
+    // Number of nodes in all buckets
+    let mut queued = 0;
+
+    // We're at `start`, with a zero cost
+    dist[start] = Some(W::zero());
+    buckets[0].push(start);
+    queued += 1;
+
+    // Examine the buckets in order of their distance
+    let mut current = 0;
+    while queued > 0 {
+        let bucket = current % buckets.len();
+
+        while let Some(position) = buckets[bucket].pop() {
+            queued -= 1;
+            let cost = dist[position].unwrap();
+
+            // Important as we may have already found a better way
+            if key(cost) != current { continue; }
+
+            if position == goal { return Some(cost); }
+
+            for edge in graph.neighbors(position) {
+                let next = match cost.checked_add(edge.cost) {
+                    Some(next) => next,
+                    // Too expensive to even represent
+                    None => continue,
+                };
+
+                if dist[edge.node].is_none_or(|d| next < d) {
+                    // Relaxation, we have now found a better way
+                    dist[edge.node] = Some(next);
+                    let len = buckets.len();
+                    buckets[key(next) % len].push(edge.node);
+                    queued += 1;
+                }
+            }
+        }
+
+        current += 1;
+    }
+
+    // Goal not reachable
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AdjacencyList;
+    use crate::dijkstra::dijkstra;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn zero_one_like_dijkstra() {
+        let mut rng = XorShift::new(0x01);
+        for _ in 0..1000 {
+            let graph = random_graph(&mut rng, 15, 40, 1);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            assert_eq!(zero_one_bfs(&graph, start, goal), dijkstra(&graph, start, goal));
+        }
+    }
+
+    #[test]
+    fn zero_other_like_dijkstra() {
+        // Any single non-zero cost works just as well as one
+        let graph: AdjacencyList<u32> = AdjacencyList::from_edges(vec![(0, 1, 7), (1, 2, 0), (0, 2, 7), (2, 3, 7), (1, 3, 0)]);
+        for goal in 0..4 {
+            assert_eq!(zero_one_bfs(&graph, 0, goal), dijkstra(&graph, 0, goal));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "0-1 BFS with costs")]
+    fn zero_one_two() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2)]);
+        zero_one_bfs(&graph, 0, 2);
+    }
+
+    #[test]
+    fn dial_like_dijkstra() {
+        let mut rng = XorShift::new(0xd1a1);
+        for max_cost in 0..8 {
+            for _ in 0..300 {
+                let graph = random_graph(&mut rng, 15, 40, max_cost);
+                let n = graph.node_count();
+                let (start, goal) = (rng.below(n), rng.below(n));
+                assert_eq!(dial(&graph, start, goal), dijkstra(&graph, start, goal));
+            }
+        }
+    }
+
+    #[test]
+    fn dial_huge_cost() {
+        // Far too many buckets, so this takes the heap instead
+        let graph: AdjacencyList<u64> = AdjacencyList::from_edges(vec![(0, 1, 1_000_000_000), (1, 2, 1), (0, 2, 2_000_000_000)]);
+        assert_eq!(dial(&graph, 0, 2), Some(1_000_000_001));
+    }
+
+    #[test]
+    fn dial_u8() {
+        let graph: AdjacencyList<u8> = AdjacencyList::from_edges(vec![(0, 1, 200), (1, 2, 100), (0, 2, 250)]);
+        assert_eq!(dial(&graph, 0, 2), Some(250));
+        assert_eq!(dial(&graph, 0, 1), Some(200));
+    }
+}
@@ -1,19 +1,26 @@
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
+use crate::csr::CsrGraph;
+use crate::graph::{Edge, Graph, Reversed};
+use crate::heap::{Handle, MeldableHeap};
+use crate::heuristic::{self, Heuristic};
+use crate::indexed_heap::IndexedHeap;
+use crate::weight::Weight;
+
 /// Adapted from https://doc.rust-lang.org/std/collections/binary_heap/
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct State {
-    cost: usize,
+pub(crate) struct State<W> {
+    cost: W,
     position: usize,
 }
 
 // The priority queue depends on `Ord`.
 // Explicitly implement the trait so the queue becomes a min-heap
 // instead of a max-heap.
-impl Ord for State {
-    fn cmp(&self, other: &State) -> Ordering {
+impl<W: Weight> Ord for State<W> {
+    fn cmp(&self, other: &State<W>) -> Ordering {
         // Notice that the we flip the ordering on costs.
         // In case of a tie we compare positions - this step is necessary
         // to make implementations of `PartialEq` and `Ord` consistent.
@@ -23,197 +30,1193 @@ impl Ord for State {
 }
 
 // `PartialOrd` needs to be implemented as well.
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &State) -> Option<Ordering> {
+impl<W: Weight> PartialOrd for State<W> {
+    fn partial_cmp(&self, other: &State<W>) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-// Each node is represented as an `usize`, for a shorter implementation.
-#[derive(Debug, Eq, PartialEq)]
-pub struct Edge {
-    node: usize,
-    cost: usize,
+/// Queue of nodes to examine next in [`dijkstra_with`] and [`dijkstra_bidir_with`], by cost.
+///
+/// Nodes may be pushed again with a lower cost while still in the queue. The outdated entries
+/// are popped later and skipped by the search.
+///
+/// Implemented for [`RadixHeap`] and the `BinaryHeap` used by [`dijkstra`].
+///
+/// [`dijkstra_with`]: fn.dijkstra_with.html
+/// [`dijkstra_bidir_with`]: fn.dijkstra_bidir_with.html
+/// [`RadixHeap`]: ../radix_heap/struct.RadixHeap.html
+/// [`dijkstra`]: fn.dijkstra.html
+pub trait Frontier<W>: Default {
+    /// Adding `node`, reached at `cost`.
+    fn push(&mut self, cost: W, node: usize);
+
+    /// Removing a node with the smallest cost, returning the cost and the node.
+    fn pop_min(&mut self) -> Option<(W, usize)>;
+
+    /// Smallest cost in the queue.
+    fn peek_min(&mut self) -> Option<W>;
 }
 
-// Dijkstra's shortest path algorithm.
+impl<W: Weight> Frontier<W> for BinaryHeap<State<W>> {
+    fn push(&mut self, cost: W, position: usize) {
+        BinaryHeap::push(self, State { cost, position });
+    }
 
-// Start at `start` and use `dist` to track the current shortest distance
-// to each node. This implementation isn't memory-efficient as it may leave duplicate
-// nodes in the queue. It also uses `usize::MAX` as a sentinel value,
-// for a simpler implementation.
-pub fn dijkstra(adj_list: &Vec<Vec<Edge>>, start: usize, goal: usize) -> Option<usize> {
-    // dist[node] = current shortest distance from `start` to `node`
-    let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
+    fn pop_min(&mut self) -> Option<(W, usize)> {
+        self.pop().map(|State { cost, position }| (cost, position))
+    }
 
-    let mut heap = BinaryHeap::new();
+    fn peek_min(&mut self) -> Option<W> {
+        self.peek().map(|state| state.cost)
+    }
+}
 
-    // We're at `start`, with a zero cost
-    dist[start] = 0;
-    heap.push(State { cost: 0, position: start });
+/// Shortest path found by a search, from `start` to `goal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPath<W = usize> {
+    /// Total cost of all edges on the path
+    pub cost: W,
+    /// Nodes on the path, starting with `start` and ending with `goal`
+    pub nodes: Vec<usize>,
+    /// Edges taken, `edges[i]` leading from `nodes[i]` to `nodes[i + 1]`
+    pub edges: Vec<Edge<W>>,
+}
+
+impl<W: Weight> ShortestPath<W> {
+    /// Reconstructing a path by stitching two predecessor chains at `meet`.
+    ///
+    /// `pred_f[node]` is the edge of the inverted graph leading back towards `start`, and
+    /// `pred_b[node]` is the edge of the graph leading on towards `goal`. Either chain ends at a
+    /// node without predecessor.
+    pub(crate) fn stitch(cost: W, meet: usize,
+                         pred_f: &[Option<Edge<W>>],
+                         pred_b: &[Option<Edge<W>>])
+        -> ShortestPath<W> {
+
+        let mut nodes = vec![meet];
+        let mut edges = Vec::new();
+
+        let mut node = meet;
+        while let Some(Edge { node: prev, cost }) = pred_f[node] {
+            edges.push(Edge { node, cost });
+            nodes.push(prev);
+            node = prev;
+        }
+        nodes.reverse();
+        edges.reverse();
+
+        let mut node = meet;
+        while let Some(edge) = pred_b.get(node).copied().flatten() {
+            edges.push(edge);
+            nodes.push(edge.node);
+            node = edge.node;
+        }
+
+        ShortestPath { cost, nodes, edges }
+    }
+}
 
-    // Examine the frontier with lower cost nodes first (min-heap)
-    while let Some(State { cost, position }) = heap.pop() {
-        // Alternatively we could have continued to find all shortest paths
-        if position == goal { return Some(cost); }
 
+/// Dijkstra's shortest path algorithm.
+///
+/// Start at `start` and use `dist` to track the current shortest distance
+/// to each node. This implementation isn't memory-efficient as it may leave duplicate
+/// nodes in the queue. Nodes not reached yet have no distance at all, and paths whose cost
+/// would overflow `W` are never taken.
+///
+/// Edge costs must not be negative.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dijkstra(&graph, 0, 2), Some(3));
+/// assert_eq!(dijkstra(&graph, 2, 0), None);
+/// ```
+pub fn dijkstra<W, G>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_path(graph, start, goal).map(|path| path.cost)
+}
+
+
+/// Dijkstra's shortest path algorithm, returning the path taken.
+///
+/// Works like [`dijkstra`], but additionally remembers for each node the edge it was last
+/// reached by. Following these predecessors back from `goal` yields the path.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_path;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let path = dijkstra_path(&graph, 0, 2).unwrap();
+/// assert_eq!(path.cost, 3);
+/// assert_eq!(path.nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`dijkstra`]: fn.dijkstra.html
+pub fn dijkstra_path<W, G>(graph: &G, start: usize, goal: usize) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_path_with::<W, G, BinaryHeap<State<W>>, _>(graph, start, goal, |_, _| true)
+}
+
+
+/// Variant of [`dijkstra_path`] that must not use the nodes with `blocked_nodes[node]` set, nor
+/// any edge `from -> to` with `blocked_edge(from, to)`.
+///
+/// Nodes past the end of `blocked_nodes` are not blocked. Returns `None` if `start` or `goal` is
+/// blocked, or no path avoids the blocked parts of the graph.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_path_masked;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4), (0, 3, 3), (3, 2, 3)]);
+/// let path = dijkstra_path_masked(&graph, 0, 2, &[false, true], |from, to| (from, to) == (0, 2)).unwrap();
+/// assert_eq!(path.nodes, vec![0, 3, 2]);
+/// ```
+///
+/// [`dijkstra_path`]: fn.dijkstra_path.html
+pub fn dijkstra_path_masked<W, G, E>(graph: &G, start: usize, goal: usize,
+                                     blocked_nodes: &[bool],
+                                     blocked_edge: E)
+    -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          E: Fn(usize, usize) -> bool
+{
+    let blocked = |node: usize| blocked_nodes.get(node).copied().unwrap_or(false);
+    if blocked(start) || blocked(goal) {
+        return None;
+    }
+    dijkstra_path_with::<W, G, BinaryHeap<State<W>>, _>(graph, start, goal, |from, to| {
+        !blocked(to) && !blocked_edge(from, to)
+    })
+}
+
+
+/// Variant of [`dijkstra`] on any [`Frontier`], such as a [`RadixHeap`] for `usize` costs.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_with;
+/// use vasa::radix_heap::RadixHeap;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dijkstra_with::<_, _, RadixHeap<_>>(&graph, 0, 2), Some(3));
+/// ```
+///
+/// [`dijkstra`]: fn.dijkstra.html
+/// [`Frontier`]: trait.Frontier.html
+/// [`RadixHeap`]: ../radix_heap/struct.RadixHeap.html
+pub fn dijkstra_with<W, G, F>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>
+{
+    dijkstra_path_with::<W, G, F, _>(graph, start, goal, |_, _| true).map(|path| path.cost)
+}
+
+
+/// Path search of [`dijkstra_path`], only taking edges `from -> to` with `allowed(from, to)`.
+///
+/// [`dijkstra_path`]: fn.dijkstra_path.html
+fn dijkstra_path_with<W, G, F, A>(graph: &G, start: usize, goal: usize, allowed: A) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>,
+          A: Fn(usize, usize) -> bool
+{
+    // Alternatively we could have continued to find all shortest paths
+    let seeds = [(start, W::zero())];
+    let (dist, pred) = dijkstra_search::<W, G, F, _, _>(graph, &seeds, allowed, |node, _| node != goal);
+    // Goal not reachable if it has no distance
+    dist[goal].map(|cost| ShortestPath::stitch(cost, goal, &pred, &[]))
+}
+
+
+/// Dijkstra's algorithm from all `seeds` `(node, cost)` at once, on any [`Frontier`].
+///
+/// Only edges `from -> to` with `allowed(from, to)` are taken. Each node is passed to `visit`
+/// with its distance as soon as that is final, before its edges are relaxed. The search stops when `visit` returns `false`, or when no nodes are left.
+/// Returns the distances, final for all nodes visited, and for each node the edge back to the
+/// node it was reached from.
+///
+/// [`Frontier`]: trait.Frontier.html
+fn dijkstra_search<W, G, F, A, V>(graph: &G, seeds: &[(usize, W)], allowed: A, mut visit: V)
+    -> (Vec<Option<W>>, Vec<Option<Edge<W>>>)
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>,
+          A: Fn(usize, usize) -> bool,
+          V: FnMut(usize, W) -> bool
+{
+    // dist[node] = current shortest distance from the closest seed to `node`
+    let mut dist: Vec<Option<W>> = vec![None; graph.node_count()];
+    // pred[node] = edge back to the node we reached `node` from, `None` for seeds
+    let mut pred: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+
+    let mut heap = F::default();
+
+    // We're at every seed, each at its own cost
+    for &(seed, cost) in seeds {
+        if dist[seed].is_none_or(|d| cost < d) {
+            dist[seed] = Some(cost);
+            heap.push(cost, seed);
+        }
+    }
+
+    // Examine the frontier with lower cost nodes first (min-heap)
+    while let Some((cost, position)) = heap.pop_min() {
         // Important as we may have already found a better way
-        if cost > dist[position] { continue; }
+        if Some(cost) > dist[position] { continue; }
+
+        // The distance of `position` is final now
+        if !visit(position, cost) {
+            break;
+        }
 
         // For each node we can reach, see if we can find a way with
         // a lower cost going through this node
-        for edge in &adj_list[position] {
-            let next = State { cost: cost + edge.cost, position: edge.node };
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            if !allowed(position, edge.node) {
+                continue;
+            }
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                // Too expensive to even represent
+                None => continue,
+            };
 
             // If so, add it to the frontier and continue
-            if next.cost < dist[next.position] {
-                heap.push(next);
+            if dist[edge.node].is_none_or(|d| next < d) {
+                heap.push(next, edge.node);
                 // Relaxation, we have now found a better way
-                dist[next.position] = next.cost;
+                dist[edge.node] = Some(next);
+                pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
             }
         }
     }
 
-    // Goal not reachable
-    None
+    (dist, pred)
 }
 
 
-fn invert_adjecency_list(adj_list: &Vec<Vec<Edge>>) -> Vec<Vec<Edge>> {
-    let len = adj_list.len();
-    let mut inverted: Vec<Vec<Edge>> = Vec::new();
+/// Dijkstra's shortest path algorithm on an [`IndexedHeap`].
+///
+/// Works like [`dijkstra`], but instead of pushing a node again whenever a better way to it is
+/// found, its key is decreased in place. Every node is in the queue at most once, so the queue
+/// takes `O(n)` memory instead of `O(m)`, and no outdated entries have to be skipped.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_indexed;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dijkstra_indexed(&graph, 0, 2), Some(3));
+/// assert_eq!(dijkstra_indexed(&graph, 2, 0), None);
+/// ```
+///
+/// [`IndexedHeap`]: ../indexed_heap/struct.IndexedHeap.html
+/// [`dijkstra`]: fn.dijkstra.html
+pub fn dijkstra_indexed<W, G>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    // dist[node] = current shortest distance from `start` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; graph.node_count()];
 
-    for _ in 0..len {
-        inverted.push(Vec::new());
-    }
+    let mut heap = IndexedHeap::new(graph.node_count());
+
+    // We're at `start`, with a zero cost
+    dist[start] = Some(W::zero());
+    heap.push(start, W::zero());
+
+    // Examine the frontier with lower cost nodes first (min-heap)
+    while let Some((position, cost)) = heap.pop() {
+        if position == goal { return Some(cost); }
 
-    for (i, l) in adj_list.iter().enumerate() {
-        for Edge { node, cost } in l {
-            // exists: i -> node with cost
-            // create: node -> i with cost
-            inverted[*node].push(Edge { node: i, cost: *cost });
+        // For each node we can reach, see if we can find a way with
+        // a lower cost going through this node
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                // Too expensive to even represent
+                None => continue,
+            };
 
+            // If so, add it to the frontier, or move it up there
+            if dist[edge.node].is_none_or(|d| next < d) {
+                heap.push_or_decrease(edge.node, next);
+                // Relaxation, we have now found a better way
+                dist[edge.node] = Some(next);
+            }
         }
     }
 
-    inverted
+    // Goal not reachable
+    None
 }
 
 
-pub fn dijkstra_bidir(adj_list: &Vec<Vec<Edge>>, start: usize, goal: usize) -> Option<usize> {
-    println!("-------------------");
-    println!("Goal: {}, Start: {}", goal, start);
+/// Dijkstra's shortest path algorithm, generic over a [`MeldableHeap`] with `decrease_key`.
+///
+/// Works like [`dijkstra_indexed`], keeping a [`Handle`] for every node in the heap to lower its
+/// key when a better way is found. With a [`FibonacciHeap`] this takes `O(m + n log n)` time.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_heap;
+/// use vasa::heap::{FibonacciHeap, PairingHeap};
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dijkstra_heap::<_, _, FibonacciHeap<_, _>>(&graph, 0, 2), Some(3));
+/// assert_eq!(dijkstra_heap::<_, _, PairingHeap<_, _>>(&graph, 2, 0), None);
+/// ```
+///
+/// [`MeldableHeap`]: ../heap/trait.MeldableHeap.html
+/// [`Handle`]: ../heap/struct.Handle.html
+/// [`FibonacciHeap`]: ../heap/struct.FibonacciHeap.html
+/// [`dijkstra_indexed`]: fn.dijkstra_indexed.html
+pub fn dijkstra_heap<W, G, H>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: MeldableHeap<W, usize>
+{
     // dist[node] = current shortest distance from `start` to `node`
-    let mut dist_f: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
-    let mut dist_b: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
-
-    let mut prio_f = BinaryHeap::new();
-    let mut prio_b = BinaryHeap::new();
+    let mut dist: Vec<Option<W>> = vec![None; graph.node_count()];
+    // handle[node] = handle of `node` in the heap, once it was pushed
+    let mut handle: Vec<Handle> = vec![Handle::null(); graph.node_count()];
 
-    let adj_list_inv = &invert_adjecency_list(adj_list);
+    let mut heap = H::new();
 
     // We're at `start`, with a zero cost
-    dist_f[start] = 0;
-    dist_b[goal]  = 0;
-
-    prio_f.push(State { cost: 0, position: start });
-    prio_b.push(State { cost: 0, position: goal });
-
-    fn check_stop(dist_f: &Vec<usize>, dist_b: &Vec<usize>,
-                  prio_f: &BinaryHeap<State>,
-                  prio_b: &BinaryHeap<State>)
-        -> Option<usize> {
-
-        println!("check for stopping condition");
-        if let Some(State { position, cost }) = prio_f.peek() {
-            let position_f = position;
-            let cost_f = cost;
-            if let Some(State { position, cost }) = prio_b.peek() {
-                let cost_b = cost;
-                for i in 0..dist_f.len() {
-                    if dist_f[i] == usize::MAX || dist_b[i] == usize::MAX {
-                        continue;
-                    }
-                    if dist_f[i] + dist_b[i] <= cost_f + cost_b {
-                        println!("At positions {} and {} with cost {} and {}", position_f, position, cost_f, cost_b);
-                        println!("Compared to {} and {}", dist_f[i], dist_b[i]);
-                        return Some(dist_f[i] + dist_b[i]);
-                    }
-                }
+    dist[start] = Some(W::zero());
+    handle[start] = heap.push(W::zero(), start);
+
+    // Examine the frontier with lower cost nodes first (min-heap)
+    while let Some((cost, position)) = heap.pop_min() {
+        if position == goal { return Some(cost); }
+
+        // For each node we can reach, see if we can find a way with
+        // a lower cost going through this node
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                // Too expensive to even represent
+                None => continue,
+            };
+
+            // If so, add it to the frontier, or move it up there. Nodes already popped are
+            // never improved, as costs are not negative.
+            match dist[edge.node] {
+                None => handle[edge.node] = heap.push(next, edge.node),
+                Some(d) if next < d => heap.decrease_key(handle[edge.node], next),
+                Some(_) => continue,
             }
+            // Relaxation, we have now found a better way
+            dist[edge.node] = Some(next);
         }
-        None
     }
 
-    while !prio_b.is_empty() || !prio_b.is_empty() {
+    // Goal not reachable
+    None
+}
+
+
+/// Shortest paths from one source to all reachable nodes, as found by [`dijkstra_all`].
+///
+/// [`dijkstra_all`]: fn.dijkstra_all.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPathTree<W = usize> {
+    /// Node all paths start at
+    source: usize,
+    /// dist[node] = shortest distance from `source` to `node`
+    dist: Vec<Option<W>>,
+    /// pred[node] = edge back to the node we reached `node` from, only meaningful for nodes
+    /// with a distance
+    pred: Vec<Option<Edge<W>>>,
+    /// Reachable nodes, in the order they were settled
+    order: Vec<usize>,
+}
+
+impl<W: Weight> ShortestPathTree<W> {
+    /// Building a tree from final distances and predecessors, settling nodes by distance.
+    pub(crate) fn from_predecessors(source: usize,
+                                    dist: Vec<Option<W>>,
+                                    pred: Vec<Option<Edge<W>>>)
+        -> ShortestPathTree<W> {
+
+        let mut order: Vec<usize> = (0..dist.len()).filter(|&node| dist[node].is_some()).collect();
+        order.sort_by_key(|&node| dist[node]);
+        ShortestPathTree { source, dist, pred, order }
+    }
+
+    /// Node all paths start at.
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// Shortest distance from the source to `node`, `None` if it is unreachable.
+    pub fn distance_to(&self, node: usize) -> Option<W> {
+        self.dist[node]
+    }
+
+    /// Node before `node` on its shortest path, `None` for the source and unreachable nodes.
+    pub fn predecessor(&self, node: usize) -> Option<usize> {
+        self.dist[node].and(self.pred[node]).map(|edge| edge.node)
+    }
+
+    /// Shortest path from the source to `node`, `None` if it is unreachable.
+    pub fn path_to(&self, node: usize) -> Option<ShortestPath<W>> {
+        self.dist[node].map(|cost| ShortestPath::stitch(cost, node, &self.pred, &[]))
+    }
+
+    /// Iterate over all reachable nodes and their distances, closest nodes first.
+    pub fn settled(&self) -> impl Iterator<Item = (usize, W)> + '_ {
+        self.order.iter().map(move |&node| (node, self.dist[node].unwrap()))
+    }
+}
+
 
-        println!("forward step");
-        if let Some(res) = dijkstra_step(adj_list, goal, &mut prio_f, &mut dist_f) {
-            return Some(res);
+/// Dijkstra's algorithm without a goal, finding shortest paths to all nodes.
+///
+/// Instead of stopping at the goal, the search continues until every node reachable from
+/// `source` is settled. A single search then answers queries for any destination.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_all;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4), (3, 0, 1)]);
+/// let tree = dijkstra_all(&graph, 0);
+/// assert_eq!(tree.distance_to(2), Some(3));
+/// assert_eq!(tree.distance_to(3), None);
+/// assert_eq!(tree.path_to(2).unwrap().nodes, vec![0, 1, 2]);
+/// assert_eq!(tree.settled().collect::<Vec<_>>(), vec![(0, 0), (1, 1), (2, 3)]);
+/// ```
+pub fn dijkstra_all<W, G>(graph: &G, source: usize) -> ShortestPathTree<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_bounded(graph, source, None, None)
+}
+
+
+/// Dijkstra's algorithm up to a cost budget, finding all nodes within `budget` of `source`.
+///
+/// Shorthand for [`dijkstra_bounded`] without a limit on settled nodes.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_within;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4), (2, 3, 1)]);
+/// let reached = dijkstra_within(&graph, 0, 3);
+/// assert_eq!(reached.settled().collect::<Vec<_>>(), vec![(0, 0), (1, 1), (2, 3)]);
+/// assert_eq!(reached.distance_to(3), None);
+/// ```
+///
+/// [`dijkstra_bounded`]: fn.dijkstra_bounded.html
+pub fn dijkstra_within<W, G>(graph: &G, source: usize, budget: W) -> ShortestPathTree<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_bounded(graph, source, Some(budget), None)
+}
+
+
+/// Dijkstra's algorithm without a goal, stopping once the frontier costs more than `max_cost`
+/// or `max_settled` nodes are settled.
+///
+/// Like [`dijkstra_all`], but the tree only holds the nodes settled before the search stopped:
+/// all nodes within `max_cost` of `source`, or the `max_settled` closest ones, whichever are
+/// fewer. Ties at the last node settled are broken arbitrarily. Without any limit this is
+/// exactly [`dijkstra_all`].
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_bounded;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4), (2, 3, 1)]);
+/// let reached = dijkstra_bounded(&graph, 0, None, Some(2));
+/// assert_eq!(reached.settled().collect::<Vec<_>>(), vec![(0, 0), (1, 1)]);
+/// let reached = dijkstra_bounded(&graph, 0, Some(10), Some(3));
+/// assert_eq!(reached.path_to(2).unwrap().nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`dijkstra_all`]: fn.dijkstra_all.html
+pub fn dijkstra_bounded<W, G>(graph: &G, source: usize, max_cost: Option<W>, max_settled: Option<usize>) -> ShortestPathTree<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    // settled[node] = final distance of `node`, only once it is settled
+    let mut settled: Vec<Option<W>> = vec![None; graph.node_count()];
+    let mut order = Vec::new();
+
+    let seeds = [(source, W::zero())];
+    let (_, pred) = dijkstra_search::<W, G, BinaryHeap<State<W>>, _, _>(graph, &seeds, |_, _| true, |node, cost| {
+        // Everything left in the frontier is out of bounds
+        if max_cost.is_some_and(|max| cost > max) || max_settled == Some(order.len()) {
+            return false;
         }
-        if let Some(res) = check_stop(&dist_f, &dist_b, &prio_f, &prio_b) {
-            return Some(res);
+        settled[node] = Some(cost);
+        order.push(node);
+        true
+    });
+
+    // Nodes reached but not settled are out of bounds, and have no distance in the tree
+    ShortestPathTree { source, dist: settled, pred, order }
+}
+
+
+/// Shortest path found by [`dijkstra_multi`] from one of several sources to one of several
+/// targets.
+///
+/// [`dijkstra_multi`]: fn.dijkstra_multi.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiPath<W = usize> {
+    /// Source the path starts at
+    pub source: usize,
+    /// Target the path ends at
+    pub target: usize,
+    /// Offset of `source` plus the cost of the path
+    pub cost: W,
+    /// Path from `source` to `target`, its cost not including the offset
+    pub path: ShortestPath<W>,
+}
+
+
+/// Dijkstra's algorithm from any of `sources` to the closest of `targets`.
+///
+/// All sources start in the queue at once, so a single search finds the closest pair, as if a
+/// virtual node were connected to every source. Returns `None` if no target is reachable from
+/// any source.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_multi;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (2, 1, 1), (1, 3, 2), (1, 4, 1)]);
+/// let best = dijkstra_multi(&graph, &[0, 2], &[3, 4]).unwrap();
+/// assert_eq!((best.source, best.target, best.cost), (2, 4, 2));
+/// assert_eq!(best.path.nodes, vec![2, 1, 4]);
+/// ```
+pub fn dijkstra_multi<W, G>(graph: &G, sources: &[usize], targets: &[usize]) -> Option<MultiPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let sources: Vec<_> = sources.iter().map(|&source| (source, W::zero())).collect();
+    dijkstra_multi_offsets(graph, &sources, targets)
+}
+
+
+/// Variant of [`dijkstra_multi`] with sources `(node, offset)` starting at the cost `offset`,
+/// such as the time it takes to get to each of them.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_multi_offsets;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (2, 1, 1), (1, 3, 2), (1, 4, 1)]);
+/// let best = dijkstra_multi_offsets(&graph, &[(0, 0), (2, 10)], &[3, 4]).unwrap();
+/// assert_eq!((best.source, best.target, best.cost), (0, 4, 6));
+/// assert_eq!(best.path.cost, 6);
+/// ```
+///
+/// [`dijkstra_multi`]: fn.dijkstra_multi.html
+pub fn dijkstra_multi_offsets<W, G>(graph: &G, sources: &[(usize, W)], targets: &[usize]) -> Option<MultiPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let mut is_target = vec![false; graph.node_count()];
+    for &target in targets {
+        is_target[target] = true;
+    }
+
+    // The first target settled is the closest one
+    let mut reached = None;
+    let (dist, pred) = dijkstra_search::<W, G, BinaryHeap<State<W>>, _, _>(graph, sources, |_, _| true, |node, _| {
+        if is_target[node] {
+            reached = Some(node);
         }
+        reached.is_none()
+    });
+
+    // No target reachable if none was settled
+    let target = reached?;
+    let cost = dist[target].unwrap();
+    let mut path = ShortestPath::stitch(cost, target, &pred, &[]);
+    // The path goes back to a source never reached more cheaply than its offset
+    let source = path.nodes[0];
+    path.cost = cost.checked_sub(dist[source].unwrap()).expect("path cost fits");
+    Some(MultiPath { source, target, cost, path })
+}
+
+
+/// Shortest distances from each of `sources` to each of `targets`, with `table[i][j]` the
+/// distance from `sources[i]` to `targets[j]`.
+///
+/// Instead of one search per pair, each search runs from one source until all targets are
+/// settled. If there are fewer targets than sources, the searches run from the targets on the
+/// inverted graph instead, until all sources are settled.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_table;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (2, 1, 1), (1, 3, 2), (1, 4, 1)]);
+/// let table = dijkstra_table(&graph, &[0, 2, 3], &[3, 4]);
+/// assert_eq!(table, vec![vec![Some(7), Some(6)],
+///                        vec![Some(3), Some(2)],
+///                        vec![Some(0), None]]);
+/// ```
+pub fn dijkstra_table<W, G>(graph: &G, sources: &[usize], targets: &[usize]) -> Vec<Vec<Option<W>>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    if targets.is_empty() || sources.len() <= targets.len() {
+        return sources.iter().map(|&source| dijkstra_to_all(graph, source, targets)).collect();
+    }
 
-        println!("backward step");
-        if let Some(res) = dijkstra_step(adj_list_inv, start, &mut prio_b, &mut dist_b) {
-            return Some(res);
+    let columns: Vec<_> = if graph.reverse_neighbors(targets[0]).is_some() {
+        let graph_inv = Reversed(graph);
+        targets.iter().map(|&target| dijkstra_to_all(&graph_inv, target, sources)).collect()
+    } else {
+        let graph_inv = CsrGraph::transpose_of(graph);
+        targets.iter().map(|&target| dijkstra_to_all(&graph_inv, target, sources)).collect()
+    };
+    (0..sources.len())
+        .map(|i| columns.iter().map(|column| column[i]).collect())
+        .collect()
+}
+
+
+/// Distances from `source` to each of `targets`, searching until all of them are settled.
+fn dijkstra_to_all<W, G>(graph: &G, source: usize, targets: &[usize]) -> Vec<Option<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    let mut is_target = vec![false; graph.node_count()];
+    let mut remaining = 0;
+    for &target in targets {
+        if !is_target[target] {
+            is_target[target] = true;
+            remaining += 1;
         }
-        if let Some(res) = check_stop(&dist_f, &dist_b, &prio_f, &prio_b) {
-            return Some(res);
+    }
+
+    let (dist, _) = dijkstra_search::<W, G, BinaryHeap<State<W>>, _, _>(graph, &[(source, W::zero())], |_, _| true, |node, _| {
+        if is_target[node] {
+            is_target[node] = false;
+            remaining -= 1;
         }
+        remaining > 0
+    });
+
+    // Every target is settled or unreachable, so its distance is final
+    targets.iter().map(|&target| dist[target]).collect()
+}
+
+
+/// Bidirectional variant of [`dijkstra`], searching from `start` and `goal` simultaneously.
+///
+/// [`dijkstra`]: fn.dijkstra.html
+pub fn dijkstra_bidir<W, G>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_bidir_path(graph, start, goal).map(|path| path.cost)
+}
+
 
+/// Bidirectional variant of [`dijkstra_path`].
+///
+/// The forward search tracks predecessors towards `start`, the backward search on the inverted
+/// graph tracks successors towards `goal`. Both chains are stitched together at the node where
+/// the searches meet. The backward search uses the [reverse neighbors] of the graph if it has
+/// them, and a transposed copy otherwise.
+///
+/// Whenever an edge is relaxed towards a node already reached from the other side, the cost of
+/// the best known path `mu` is updated. The search stops as soon as the two frontiers together
+/// can no longer beat it, that is once `top_f + top_b >= mu`.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_bidir_path;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let path = dijkstra_bidir_path(&graph, 0, 2).unwrap();
+/// assert_eq!(path.cost, 3);
+/// assert_eq!(path.nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`dijkstra_path`]: fn.dijkstra_path.html
+/// [reverse neighbors]: ../graph/trait.Graph.html#method.reverse_neighbors
+pub fn dijkstra_bidir_path<W, G>(graph: &G, start: usize, goal: usize) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    dijkstra_bidir_path_with::<W, G, BinaryHeap<State<W>>>(graph, start, goal)
+}
+
+
+/// Variant of [`dijkstra_bidir`] on any [`Frontier`], such as a [`RadixHeap`] for `usize` costs.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::dijkstra_bidir_with;
+/// use vasa::radix_heap::RadixHeap;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// assert_eq!(dijkstra_bidir_with::<_, _, RadixHeap<_>>(&graph, 0, 2), Some(3));
+/// ```
+///
+/// [`dijkstra_bidir`]: fn.dijkstra_bidir.html
+/// [`Frontier`]: trait.Frontier.html
+/// [`RadixHeap`]: ../radix_heap/struct.RadixHeap.html
+pub fn dijkstra_bidir_with<W, G, F>(graph: &G, start: usize, goal: usize) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>
+{
+    dijkstra_bidir_path_with::<W, G, F>(graph, start, goal).map(|path| path.cost)
+}
+
+
+fn dijkstra_bidir_path_with<W, G, F>(graph: &G, start: usize, goal: usize) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>
+{
+    // The backward search follows incoming edges, listed by the graph itself if it can
+    if graph.reverse_neighbors(goal).is_some() {
+        dijkstra_bidir_search::<W, G, _, F>(graph, &Reversed(graph), start, goal)
+    } else {
+        dijkstra_bidir_search::<W, G, _, F>(graph, &CsrGraph::transpose_of(graph), start, goal)
     }
-    None
 }
 
 
-fn dijkstra_step(adj_list: &Vec<Vec<Edge>>,
-                 goal: usize,
-                 heap: &mut BinaryHeap<State>,
-                 dist: &mut Vec<usize>) 
-    -> Option<usize> {
+/// Bidirectional search, with `graph_inv` having the edges of `graph` reversed.
+fn dijkstra_bidir_search<W, G, R, F>(graph: &G, graph_inv: &R, start: usize, goal: usize) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          R: Graph<Weight = W>,
+          F: Frontier<W>
+{
+    // dist_f[node] = current shortest distance from `start` to `node`
+    // dist_b[node] = current shortest distance from `node` to `goal`
+    let mut dist_f: Vec<Option<W>> = vec![None; graph.node_count()];
+    let mut dist_b: Vec<Option<W>> = vec![None; graph.node_count()];
+
+    let mut pred_f: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+    let mut pred_b: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+
+    let mut prio_f = F::default();
+    let mut prio_b = F::default();
+
+    // We're at `start` and `goal`, with a zero cost
+    dist_f[start] = Some(W::zero());
+    dist_b[goal]  = Some(W::zero());
+
+    prio_f.push(W::zero(), start);
+    prio_b.push(W::zero(), goal);
+
+    // Best path found so far, and the node where it passes from one search to the other
+    let mut best = None;
+    if start == goal {
+        best = Some(Meeting { cost: W::zero(), node: start });
+    }
+
+    while let (Some(top_f), Some(top_b)) = (prio_f.peek_min(), prio_b.peek_min()) {
+        // Stopping criterion: no path through the frontiers can be shorter anymore
+        if let Some(Meeting { cost, .. }) = best {
+            if top_f.saturating_add(top_b) >= cost {
+                break;
+            }
+        }
+
+        // Advance the search whose frontier is closer
+        if top_f <= top_b {
+            dijkstra_step(graph, &mut prio_f, &mut dist_f, &mut pred_f, &dist_b, &mut best);
+        } else {
+            dijkstra_step(graph_inv, &mut prio_b, &mut dist_b, &mut pred_b, &dist_f, &mut best);
+        }
+    }
+
+    // Goal not reachable if the searches never met
+    best.map(|Meeting { cost, node }| ShortestPath::stitch(cost, node, &pred_f, &pred_b))
+}
+
+
+/// Best connection between forward and backward search of [`dijkstra_bidir_path`].
+///
+/// [`dijkstra_bidir_path`]: fn.dijkstra_bidir_path.html
+#[derive(Copy, Clone, Debug)]
+struct Meeting<W> {
+    cost: W,
+    node: usize,
+}
+
+
+/// Settle the next node of one direction of a bidirectional search.
+///
+/// `other` are the distances of the opposite search, used to update the `best` meeting point on
+/// every relaxation.
+fn dijkstra_step<W, G, F>(graph: &G,
+                          heap: &mut F,
+                          dist: &mut [Option<W>],
+                          pred: &mut [Option<Edge<W>>],
+                          other: &[Option<W>],
+                          best: &mut Option<Meeting<W>>)
+    where W: Weight,
+          G: Graph<Weight = W>,
+          F: Frontier<W>
+{
 
     // Examine the frontier with lower cost nodes first (min-heap)
-    if let Some(State { cost, position }) = heap.pop() {
-        println!("I: {}, P: {}", position, cost);
-        // We found a shortest path!
+    if let Some((cost, position)) = heap.pop_min() {
+        // Important as we may have already found a better way
+        if Some(cost) > dist[position] { return; }
+
+        // For each node we can reach, see if we can find a way with
+        // a lower cost going through this node
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            let cost = match cost.checked_add(edge.cost) {
+                Some(cost) => cost,
+                // Too expensive to even represent
+                None => continue,
+            };
+
+            // If so, add it to the frontier and continue
+            if dist[edge.node].is_none_or(|d| cost < d) {
+                heap.push(cost, edge.node);
+                // Relaxation, we have now found a better way
+                dist[edge.node] = Some(cost);
+                pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
+
+                // The other search has been here already, so we found a path
+                let total = other[edge.node].and_then(|other| cost.checked_add(other));
+                if let Some(total) = total {
+                    if best.is_none_or(|best| total < best.cost) {
+                        *best = Some(Meeting { cost: total, node: edge.node });
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+/// A* search, Dijkstra's algorithm guided by a [`Heuristic`].
+///
+/// Nodes are examined in order of their distance from `start` plus the estimated remaining cost
+/// to `goal`, so nodes leading away from the goal are examined later, or not at all. With the
+/// heuristic constantly zero this is exactly [`dijkstra`].
+///
+/// The heuristic must be consistent, which in debug builds is checked for every edge relaxed.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::astar_shortest_path;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let h = |node: usize| [3, 2, 0][node];
+/// assert_eq!(astar_shortest_path(&graph, 0, 2, &h), Some(3));
+/// ```
+///
+/// [`Heuristic`]: ../heuristic/trait.Heuristic.html
+/// [`dijkstra`]: fn.dijkstra.html
+pub fn astar_shortest_path<W, G, H>(graph: &G, start: usize, goal: usize, heuristic: &H) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: Heuristic<W>
+{
+    astar_path(graph, start, goal, heuristic).map(|path| path.cost)
+}
+
+
+/// A* search, returning the path taken.
+///
+/// Works like [`astar_shortest_path`], tracking predecessors like [`dijkstra_path`].
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::astar_path;
+/// use vasa::heuristic::Euclidean;
+/// let coords = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (1.0, 5.0)];
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1), (0, 3, 6), (3, 2, 6)]);
+/// let path = astar_path(&graph, 0, 2, &Euclidean::new(&coords, 2)).unwrap();
+/// assert_eq!(path.nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`astar_shortest_path`]: fn.astar_shortest_path.html
+/// [`dijkstra_path`]: fn.dijkstra_path.html
+pub fn astar_path<W, G, H>(graph: &G, start: usize, goal: usize, heuristic: &H) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: Heuristic<W>
+{
+    debug_assert!(heuristic.estimate(goal) == W::zero(),
+                  "heuristic at goal is {:?}, not zero", heuristic.estimate(goal));
+
+    // dist[node] = current shortest distance from `start` to `node`
+    let mut dist: Vec<Option<W>> = vec![None; graph.node_count()];
+    // pred[node] = edge back to the node we reached `node` from
+    let mut pred: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+
+    // The heap is ordered by distance plus estimate
+    let mut heap = BinaryHeap::new();
+
+    // We're at `start`, with a zero cost
+    dist[start] = Some(W::zero());
+    heap.push(State { cost: heuristic.estimate(start), position: start });
+
+    // Examine the frontier with the most promising nodes first (min-heap)
+    while let Some(State { cost, position }) = heap.pop() {
+        let dist_position = dist[position].expect("queued nodes have a distance");
+
+        // Important as we may have already found a better way
+        if cost > dist_position.saturating_add(heuristic.estimate(position)) { continue; }
+
         if position == goal {
-            println!("Found {} == {} at {}", position, goal, cost);
-            return Some(cost);
+            return Some(ShortestPath::stitch(dist_position, goal, &pred, &[]));
         }
-        // Stopping criteria: a vertex is done from both sides
-        // needs to be checked for in 'parent' function
 
         // For each node we can reach, see if we can find a way with
         // a lower cost going through this node
-        for edge in &adj_list[position] {
-            let cost = cost + edge.cost;
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            heuristic::debug_check_consistent(heuristic, position, edge.node, edge.cost);
+
+            let next = match dist_position.checked_add(edge.cost) {
+                Some(cost) => cost,
+                // Too expensive to even represent
+                None => continue,
+            };
 
             // If so, add it to the frontier and continue
-            if cost < dist[edge.node] {
-                println!("{} < {} !", cost, dist[edge.node]);
-                heap.push(State { position: edge.node, cost });
+            if dist[edge.node].is_none_or(|d| next < d) {
+                let estimate = match next.checked_add(heuristic.estimate(edge.node)) {
+                    Some(estimate) => estimate,
+                    None => continue,
+                };
+                heap.push(State { cost: estimate, position: edge.node });
                 // Relaxation, we have now found a better way
-                dist[edge.node] = cost;
+                dist[edge.node] = Some(next);
+                pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
             }
         }
     }
 
-    // Goal not reached in this step
+    // Goal not reachable
     None
 }
 
 
-// pub fn astar_shortest_path(adj_list: &Vec<Vec<Edge>>, start: usize, goal: usize) -> Option<usize> {
-//     unimplemented!()
-// }
+/// Bidirectional variant of [`astar_shortest_path`].
+///
+/// [`astar_shortest_path`]: fn.astar_shortest_path.html
+pub fn astar_bidir_shortest_path<W, G, H, B>(graph: &G, start: usize, goal: usize, heuristic: &H, backward: &B) -> Option<W>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: Heuristic<W>,
+          B: Heuristic<W>
+{
+    astar_bidir_path(graph, start, goal, heuristic, backward).map(|path| path.cost)
+}
+
+
+/// Bidirectional A* search, guided towards `goal` by `heuristic` and back towards `start` by
+/// `backward`, which estimates the cost from `start` to each node.
+///
+/// Both searches are ordered by distance plus estimate, and each of their frontiers bounds the
+/// cost of every path not found yet. The search stops as soon as either frontier can no longer
+/// beat the best known path. The backward search runs on the inverted graph as in
+/// [`dijkstra_bidir_path`].
+///
+/// Both heuristics must be consistent. With both constantly zero this is a bidirectional
+/// Dijkstra stopping later than [`dijkstra_bidir_path`].
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::dijkstra::astar_bidir_path;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let h = |node: usize| [3, 2, 0][node];
+/// let backward = |node: usize| [0, 1, 3][node];
+/// let path = astar_bidir_path(&graph, 0, 2, &h, &backward).unwrap();
+/// assert_eq!(path.cost, 3);
+/// assert_eq!(path.nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`dijkstra_bidir_path`]: fn.dijkstra_bidir_path.html
+pub fn astar_bidir_path<W, G, H, B>(graph: &G, start: usize, goal: usize, heuristic: &H, backward: &B) -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: Heuristic<W>,
+          B: Heuristic<W>
+{
+    debug_assert!(heuristic.estimate(goal) == W::zero(),
+                  "heuristic at goal is {:?}, not zero", heuristic.estimate(goal));
+    debug_assert!(backward.estimate(start) == W::zero(),
+                  "backward heuristic at start is {:?}, not zero", backward.estimate(start));
+
+    if graph.reverse_neighbors(goal).is_some() {
+        astar_bidir_search(graph, &Reversed(graph), start, goal, heuristic, backward)
+    } else {
+        astar_bidir_search(graph, &CsrGraph::transpose_of(graph), start, goal, heuristic, backward)
+    }
+}
+
+
+/// Bidirectional A* search, with `graph_inv` having the edges of `graph` reversed.
+fn astar_bidir_search<W, G, R, H, B>(graph: &G, graph_inv: &R, start: usize, goal: usize,
+                                     heuristic: &H, backward: &B)
+    -> Option<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>,
+          R: Graph<Weight = W>,
+          H: Heuristic<W>,
+          B: Heuristic<W>
+{
+    // dist_f[node] = current shortest distance from `start` to `node`
+    // dist_b[node] = current shortest distance from `node` to `goal`
+    let mut dist_f: Vec<Option<W>> = vec![None; graph.node_count()];
+    let mut dist_b: Vec<Option<W>> = vec![None; graph.node_count()];
+
+    let mut pred_f: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+    let mut pred_b: Vec<Option<Edge<W>>> = vec![None; graph.node_count()];
+
+    // Both heaps are ordered by distance plus estimate
+    let mut heap_f = BinaryHeap::new();
+    let mut heap_b = BinaryHeap::new();
+
+    // We're at `start` and `goal`, with a zero cost
+    dist_f[start] = Some(W::zero());
+    dist_b[goal]  = Some(W::zero());
+
+    Frontier::push(&mut heap_f, heuristic.estimate(start), start);
+    Frontier::push(&mut heap_b, backward.estimate(goal), goal);
+
+    // Best path found so far, and the node where it passes from one search to the other
+    let mut best = None;
+    if start == goal {
+        best = Some(Meeting { cost: W::zero(), node: start });
+    }
+
+    while let (Some(top_f), Some(top_b)) = (heap_f.peek_min(), heap_b.peek_min()) {
+        // Stopping criterion: either frontier bounds all paths not found yet
+        if let Some(Meeting { cost, .. }) = best {
+            if top_f >= cost || top_b >= cost {
+                break;
+            }
+        }
+
+        // Advance the search whose frontier is more promising
+        if top_f <= top_b {
+            astar_step(graph, heuristic, &mut heap_f, &mut dist_f, &mut pred_f, &dist_b, &mut best);
+        } else {
+            astar_step(graph_inv, backward, &mut heap_b, &mut dist_b, &mut pred_b, &dist_f, &mut best);
+        }
+    }
+
+    // Goal not reachable if the searches never met
+    best.map(|Meeting { cost, node }| ShortestPath::stitch(cost, node, &pred_f, &pred_b))
+}
+
+
+/// Settle the next node of one direction of a bidirectional A* search, like [`dijkstra_step`]
+/// with the heap ordered by distance plus estimate.
+///
+/// [`dijkstra_step`]: fn.dijkstra_step.html
+fn astar_step<W, G, H>(graph: &G,
+                       heuristic: &H,
+                       heap: &mut BinaryHeap<State<W>>,
+                       dist: &mut [Option<W>],
+                       pred: &mut [Option<Edge<W>>],
+                       other: &[Option<W>],
+                       best: &mut Option<Meeting<W>>)
+    where W: Weight,
+          G: Graph<Weight = W>,
+          H: Heuristic<W>
+{
+    if let Some((cost, position)) = heap.pop_min() {
+        let dist_position = dist[position].expect("queued nodes have a distance");
+
+        // Important as we may have already found a better way
+        if cost > dist_position.saturating_add(heuristic.estimate(position)) { return; }
+
+        for edge in graph.neighbors(position) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            heuristic::debug_check_consistent(heuristic, position, edge.node, edge.cost);
+
+            let next = match dist_position.checked_add(edge.cost) {
+                Some(cost) => cost,
+                // Too expensive to even represent
+                None => continue,
+            };
+
+            if dist[edge.node].is_none_or(|d| next < d) {
+                let estimate = match next.checked_add(heuristic.estimate(edge.node)) {
+                    Some(estimate) => estimate,
+                    None => continue,
+                };
+                Frontier::push(heap, estimate, edge.node);
+                // Relaxation, we have now found a better way
+                dist[edge.node] = Some(next);
+                pred[edge.node] = Some(Edge { node: position, cost: edge.cost });
+
+                // The other search has been here already, so we found a path
+                let total = other[edge.node].and_then(|other| next.checked_add(other));
+                if let Some(total) = total {
+                    if best.is_none_or(|best| total < best.cost) {
+                        *best = Some(Meeting { cost: total, node: edge.node });
+                    }
+                }
+            }
+        }
+    }
+}
 
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::{AdjacencyList, ImplicitGraph};
+    use crate::heap::{BinomialHeap, FibonacciHeap, LeftistHeap, PairingHeap};
+    use crate::heuristic::Euclidean;
+    use crate::matrix::DistanceMatrix;
+    use crate::radix_heap::RadixHeap;
+    use crate::testutil::{random_graph, XorShift};
+    use crate::weight::OrderedFloat;
+
+    // Check that `path` is a walk from `start` to `goal` along edges of `graph` adding up to its cost.
+    fn assert_path(graph: &AdjacencyList, path: &ShortestPath, start: usize, goal: usize) {
+        assert_eq!(path.nodes.first(), Some(&start));
+        assert_eq!(path.nodes.last(), Some(&goal));
+        assert_eq!(path.nodes.len(), path.edges.len() + 1);
+        for (from, edge) in path.nodes.iter().zip(&path.edges) {
+            assert!(graph.neighbors(*from).contains(edge));
+        }
+        assert_eq!(path.edges.iter().map(|e| e.cost).sum::<usize>(), path.cost);
+    }
 
     #[test]
     fn test_dijkstra() {
@@ -238,7 +1241,7 @@ mod tests {
     // The graph is represented as an adjacency list where each index,
     // corresponding to a node value, has a list of outgoing edges.
     // Chosen for its efficiency.
-    let graph = vec![
+    let graph = AdjacencyList::from(vec![
         // Node 0
         vec![Edge { node: 2, cost: 10 },
              Edge { node: 1, cost: 1 }],
@@ -252,7 +1255,7 @@ mod tests {
         vec![Edge { node: 0, cost: 7 },
              Edge { node: 4, cost: 2 }],
         // Node 4
-        vec![]];
+        vec![]]);
 
     assert_eq!(dijkstra(&graph, 0, 1), Some(1));
     assert_eq!(dijkstra(&graph, 0, 3), Some(3));
@@ -284,7 +1287,7 @@ mod tests {
     // The graph is represented as an adjacency list where each index,
     // corresponding to a node value, has a list of outgoing edges.
     // Chosen for its efficiency.
-    let graph = vec![
+    let graph = AdjacencyList::from(vec![
         // Node 0
         vec![Edge { node: 2, cost: 10 },
              Edge { node: 1, cost: 1 }],
@@ -298,7 +1301,7 @@ mod tests {
         vec![Edge { node: 0, cost: 7 },
              Edge { node: 4, cost: 2 }],
         // Node 4
-        vec![]];
+        vec![]]);
 
     assert_eq!(dijkstra_bidir(&graph, 0, 1), Some(1));
     assert_eq!(dijkstra_bidir(&graph, 0, 3), Some(3));
@@ -307,55 +1310,446 @@ mod tests {
     assert_eq!(dijkstra_bidir(&graph, 4, 0), None);
     }
 
-    // #[test]
-    // fn test_astar_dijkstra() {
-    // // This is the directed graph we're going to use.
-    // // The node numbers correspond to the different states,
-    // // and the edge weights symbolize the cost of moving
-    // // from one node to another.
-    // // Note that the edges are one-way.
-    // //
-    // //                  7
-    // //          +-----------------+
-    // //          |                 |
-    // //          v   1        2    |  2
-    // //          0 -----> 1 -----> 3 ---> 4
-    // //          |        ^        ^      ^
-    // //          |        | 1      |      |
-    // //          |        |        | 3    | 1
-    // //          +------> 2 -------+      |
-    // //           10      |               |
-    // //                   +---------------+
-    // //
-    // // The graph is represented as an adjacency list where each index,
-    // // corresponding to a node value, has a list of outgoing edges.
-    // // Chosen for its efficiency.
-    // let graph = vec![
-    //     // Node 0
-    //     vec![Edge { node: 2, cost: 10 },
-    //          Edge { node: 1, cost: 1 }],
-    //     // Node 1
-    //     vec![Edge { node: 3, cost: 2 }],
-    //     // Node 2
-    //     vec![Edge { node: 1, cost: 1 },
-    //          Edge { node: 3, cost: 3 },
-    //          Edge { node: 4, cost: 1 }],
-    //     // Node 3
-    //     vec![Edge { node: 0, cost: 7 },
-    //          Edge { node: 4, cost: 2 }],
-    //     // Node 4
-    //     vec![]];
-
-    // assert_eq!(astar_shortest_path(&graph, 0, 1), Some(1));
-    // assert_eq!(astar_shortest_path(&graph, 0, 3), Some(3));
-    // assert_eq!(astar_shortest_path(&graph, 3, 0), Some(7));
-    // assert_eq!(astar_shortest_path(&graph, 0, 4), Some(5));
-    // assert_eq!(astar_shortest_path(&graph, 4, 0), None);
-    // }
+    #[test]
+    fn test_dijkstra_path() {
+        let graph = AdjacencyList::from_edges(vec![
+            (0, 2, 10), (0, 1, 1), (1, 3, 2), (2, 1, 1),
+            (2, 3, 3), (2, 4, 1), (3, 0, 7), (3, 4, 2)]);
+
+        let path = dijkstra_path(&graph, 0, 4).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes, vec![0, 1, 3, 4]);
+        assert_eq!(path.edges, vec![Edge { node: 1, cost: 1 },
+                                    Edge { node: 3, cost: 2 },
+                                    Edge { node: 4, cost: 2 }]);
+
+        let path = dijkstra_path(&graph, 2, 2).unwrap();
+        assert_eq!(path.cost, 0);
+        assert_eq!(path.nodes, vec![2]);
+        assert!(path.edges.is_empty());
+
+        assert_eq!(dijkstra_path(&graph, 4, 0), None);
+    }
+
+    #[test]
+    fn test_dijkstra_bidir_path() {
+        let graph = AdjacencyList::from_edges(vec![
+            (0, 2, 10), (0, 1, 1), (1, 3, 2), (2, 1, 1),
+            (2, 3, 3), (2, 4, 1), (3, 0, 7), (3, 4, 2)]);
+
+        for (start, goal) in [(0, 4), (3, 1), (2, 0), (1, 1)] {
+            let path = dijkstra_bidir_path(&graph, start, goal).unwrap();
+            assert_eq!(Some(path.cost), dijkstra(&graph, start, goal));
+            assert_path(&graph, &path, start, goal);
+        }
+        assert_eq!(dijkstra_bidir_path(&graph, 4, 0), None);
+    }
+
+    #[test]
+    fn test_dijkstra_bidir_random() {
+        let mut rng = XorShift::new(0x5eed);
+        for _ in 0..3000 {
+            let graph = random_graph(&mut rng, 12, 30, 10);
+            let n = graph.node_count();
+            let start = rng.below(n);
+            let goal = rng.below(n);
+
+            let expected = dijkstra(&graph, start, goal);
+            let path = dijkstra_bidir_path(&graph, start, goal);
+            assert_eq!(path.as_ref().map(|p| p.cost), expected);
+            if let Some(path) = path {
+                assert_path(&graph, &path, start, goal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_bidir_zero_weights() {
+        let mut rng = XorShift::new(42);
+        for _ in 0..1000 {
+            let graph = random_graph(&mut rng, 8, 20, 1);
+            let n = graph.node_count();
+            for start in 0..n {
+                for goal in 0..n {
+                    assert_eq!(dijkstra_bidir(&graph, start, goal), dijkstra(&graph, start, goal));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        let mut rng = XorShift::new(99);
+        for _ in 0..500 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let source = rng.below(graph.node_count());
+            let tree = dijkstra_all(&graph, source);
+            assert_eq!(tree.source(), source);
+
+            for goal in 0..graph.node_count() {
+                assert_eq!(tree.distance_to(goal), dijkstra(&graph, source, goal));
+                match tree.path_to(goal) {
+                    Some(path) => assert_path(&graph, &path, source, goal),
+                    None => assert_eq!(tree.distance_to(goal), None),
+                }
+            }
+
+            let settled: Vec<_> = tree.settled().collect();
+            assert_eq!(settled.len(), (0..graph.node_count()).filter(|&n| tree.distance_to(n).is_some()).count());
+            assert_eq!(settled[0], (source, 0));
+            assert!(settled.windows(2).all(|w| w[0].1 <= w[1].1));
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_indexed() {
+        let mut rng = XorShift::new(12);
+        for _ in 0..1000 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            assert_eq!(dijkstra_indexed(&graph, start, goal), dijkstra(&graph, start, goal));
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_heap() {
+        let mut rng = XorShift::new(13);
+        for _ in 0..1000 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let expected = dijkstra(&graph, start, goal);
+            assert_eq!(dijkstra_heap::<_, _, FibonacciHeap<_, _>>(&graph, start, goal), expected);
+            assert_eq!(dijkstra_heap::<_, _, PairingHeap<_, _>>(&graph, start, goal), expected);
+            assert_eq!(dijkstra_heap::<_, _, BinomialHeap<_, _>>(&graph, start, goal), expected);
+            assert_eq!(dijkstra_heap::<_, _, LeftistHeap<_, _>>(&graph, start, goal), expected);
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_radix() {
+        let mut rng = XorShift::new(14);
+        for i in 0..1000 {
+            // Also with costs spanning many buckets
+            let max_cost = if i % 2 == 0 { 10 } else { 1 << 40 };
+            let graph = random_graph(&mut rng, 15, 40, max_cost);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let expected = dijkstra(&graph, start, goal);
+            assert_eq!(dijkstra_with::<_, _, RadixHeap<_>>(&graph, start, goal), expected);
+            assert_eq!(dijkstra_bidir_with::<_, _, RadixHeap<_>>(&graph, start, goal), expected);
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_indexed_memory() {
+        // Every one of `k` targets is improved by each of `k` middle nodes in turn:
+        //   0 -> middle i with cost i, middle i -> target j with cost 2k - 2i
+        let k = 20;
+        let mut graph = AdjacencyList::new();
+        for _ in 0..2 * k + 1 {
+            graph.add_node();
+        }
+        for i in 1..=k {
+            graph.add_edge(0, i, i);
+            for j in k + 1..=2 * k {
+                graph.add_edge(i, j, 2 * k - 2 * i);
+            }
+        }
+
+        // Largest size of the queue of a complete search, with lazy deletion
+        let mut dist = vec![usize::MAX; graph.node_count()];
+        let mut lazy = BinaryHeap::new();
+        let mut lazy_peak = 0;
+        dist[0] = 0;
+        lazy.push(State { cost: 0, position: 0 });
+        while let Some(State { cost, position }) = lazy.pop() {
+            if cost > dist[position] { continue; }
+            for edge in graph.neighbors(position) {
+                if cost + edge.cost < dist[edge.node] {
+                    dist[edge.node] = cost + edge.cost;
+                    lazy.push(State { cost: cost + edge.cost, position: edge.node });
+                }
+            }
+            lazy_peak = lazy_peak.max(lazy.len());
+        }
+
+        // Same for decreasing keys
+        let mut dist = vec![usize::MAX; graph.node_count()];
+        let mut indexed = IndexedHeap::new(graph.node_count());
+        let mut indexed_peak = 0;
+        dist[0] = 0;
+        indexed.push(0, 0);
+        while let Some((position, cost)) = indexed.pop() {
+            for edge in graph.neighbors(position) {
+                if cost + edge.cost < dist[edge.node] {
+                    dist[edge.node] = cost + edge.cost;
+                    indexed.push_or_decrease(edge.node, cost + edge.cost);
+                }
+            }
+            indexed_peak = indexed_peak.max(indexed.len());
+        }
+
+        assert_eq!(indexed_peak, 2 * k - 1);
+        assert!(lazy_peak > k * k / 2, "lazy queue peaked at {}", lazy_peak);
+        assert_eq!(dijkstra_indexed(&graph, 0, 2 * k), dijkstra(&graph, 0, 2 * k));
+    }
+
+    #[test]
+    fn test_dijkstra_representations() {
+        let mut rng = XorShift::new(116);
+        for _ in 0..300 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let matrix = DistanceMatrix::from(&graph);
+            let implicit = ImplicitGraph::new(graph.node_count(), |node| graph.neighbors(node).iter().copied());
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let expected = dijkstra(&graph, start, goal);
+
+            assert_eq!(dijkstra(&matrix, start, goal), expected);
+            assert_eq!(dijkstra(&implicit, start, goal), expected);
+            // Bidirectional searches on the reverse neighbors of the matrix and on a transposed
+            // copy of the implicit graph
+            assert_eq!(dijkstra_bidir(&implicit, start, goal), expected);
+            let path = dijkstra_bidir_path(&matrix, start, goal);
+            assert_eq!(path.as_ref().map(|path| path.cost), expected);
+            if let Some(path) = path {
+                assert_path(&graph, &path, start, goal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_u32() {
+        let graph: AdjacencyList<u32> = AdjacencyList::from_edges(vec![(0, 1, 3), (1, 2, 4), (0, 2, 9)]);
+        assert_eq!(dijkstra(&graph, 0, 2), Some(7));
+        assert_eq!(dijkstra_bidir(&graph, 0, 2), Some(7));
+    }
+
+    #[test]
+    fn test_dijkstra_overflow() {
+        // The path via node 1 would overflow, so only the direct edge remains.
+        let big = u64::MAX / 2 + 1;
+        let graph: AdjacencyList<u64> = AdjacencyList::from_edges(vec![(0, 1, big), (1, 2, big), (0, 2, u64::MAX)]);
+        assert_eq!(dijkstra(&graph, 0, 2), Some(u64::MAX));
+        assert_eq!(dijkstra_bidir(&graph, 0, 2), Some(u64::MAX));
+
+        // No representable path at all.
+        let graph: AdjacencyList<u8> = AdjacencyList::from_edges(vec![(0, 1, 200), (1, 2, 100)]);
+        assert_eq!(dijkstra(&graph, 0, 2), None);
+        assert_eq!(dijkstra_bidir(&graph, 0, 2), None);
+    }
+
+    #[test]
+    fn test_dijkstra_f64() {
+        let graph = AdjacencyList::from_edges(vec![
+            (0, 1, OrderedFloat(0.5)),
+            (1, 2, OrderedFloat(0.25)),
+            (0, 2, OrderedFloat(1.0))]);
+        assert_eq!(dijkstra(&graph, 0, 2), Some(OrderedFloat(0.75)));
+        assert_eq!(dijkstra_bidir(&graph, 0, 2), Some(OrderedFloat(0.75)));
+        assert_eq!(dijkstra_path(&graph, 0, 2).unwrap().nodes, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_astar_dijkstra() {
+    // This is the directed graph we're going to use.
+    // The node numbers correspond to the different states,
+    // and the edge weights symbolize the cost of moving
+    // from one node to another.
+    // Note that the edges are one-way.
+    //
+    //                  7
+    //          +-----------------+
+    //          |                 |
+    //          v   1        2    |  2
+    //          0 -----> 1 -----> 3 ---> 4
+    //          |        ^        ^      ^
+    //          |        | 1      |      |
+    //          |        |        | 3    | 1
+    //          +------> 2 -------+      |
+    //           10      |               |
+    //                   +---------------+
+    //
+    // The graph is represented as an adjacency list where each index,
+    // corresponding to a node value, has a list of outgoing edges.
+    // Chosen for its efficiency.
+    let graph = AdjacencyList::from(vec![
+        // Node 0
+        vec![Edge { node: 2, cost: 10 },
+             Edge { node: 1, cost: 1 }],
+        // Node 1
+        vec![Edge { node: 3, cost: 2 }],
+        // Node 2
+        vec![Edge { node: 1, cost: 1 },
+             Edge { node: 3, cost: 3 },
+             Edge { node: 4, cost: 1 }],
+        // Node 3
+        vec![Edge { node: 0, cost: 7 },
+             Edge { node: 4, cost: 2 }],
+        // Node 4
+        vec![]]);
+
+    // Lower bounds on the distance to node 4
+    let no_estimate = |_: usize| 0;
+    let to_four = |node: usize| [3, 2, 1, 2, 0][node];
+
+    assert_eq!(astar_shortest_path(&graph, 0, 1, &no_estimate), Some(1));
+    assert_eq!(astar_shortest_path(&graph, 0, 3, &no_estimate), Some(3));
+    assert_eq!(astar_shortest_path(&graph, 3, 0, &no_estimate), Some(7));
+    assert_eq!(astar_shortest_path(&graph, 0, 4, &to_four), Some(5));
+    assert_eq!(astar_shortest_path(&graph, 4, 0, &no_estimate), None);
+    }
+
+    #[test]
+    fn test_astar_random_geometric() {
+        // Nodes on a plane, edges cost at least their length
+        let mut rng = XorShift::new(7);
+        for _ in 0..500 {
+            let n = 2 + rng.below(20);
+            let coords: Vec<_> = (0..n)
+                .map(|_| (rng.below(100) as f64, rng.below(100) as f64))
+                .collect();
+            let mut graph = AdjacencyList::with_capacity(n);
+            for _ in 0..n {
+                graph.add_node();
+            }
+            for _ in 0..rng.below(4 * n) {
+                let (u, v) = (rng.below(n), rng.below(n));
+                let (dx, dy) = (coords[u].0 - coords[v].0, coords[u].1 - coords[v].1);
+                let length = dx.hypot(dy).ceil() as usize;
+                graph.add_edge(u, v, length + rng.below(10));
+            }
+            let goal = rng.below(n);
+            for start in 0..n {
+                let expected = dijkstra(&graph, start, goal);
+                let path = astar_path(&graph, start, goal, &Euclidean::new(&coords, goal));
+                assert_eq!(path.as_ref().map(|p| p.cost), expected);
+                if let Some(path) = path {
+                    assert_path(&graph, &path, start, goal);
+                }
+                let path = astar_bidir_path(&graph, start, goal,
+                                            &Euclidean::new(&coords, goal),
+                                            &Euclidean::new(&coords, start));
+                assert_eq!(path.as_ref().map(|p| p.cost), expected);
+                if let Some(path) = path {
+                    assert_path(&graph, &path, start, goal);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_bounded() {
+        let mut rng = XorShift::new(24);
+        for _ in 0..500 {
+            let graph = random_graph(&mut rng, 20, 60, 10);
+            let n = graph.node_count();
+            let source = rng.below(n);
+            let all = dijkstra_all(&graph, source);
+
+            let budget = rng.below(30);
+            let within = dijkstra_within(&graph, source, budget);
+            for node in 0..n {
+                let expected = all.distance_to(node).filter(|&d| d <= budget);
+                assert_eq!(within.distance_to(node), expected);
+                if expected.is_none() {
+                    assert_eq!(within.predecessor(node), None);
+                }
+                if let Some(path) = within.path_to(node) {
+                    assert_path(&graph, &path, source, node);
+                }
+            }
+
+            let count = rng.below(n + 2);
+            let nearest = dijkstra_bounded(&graph, source, None, Some(count));
+            let settled: Vec<_> = nearest.settled().collect();
+            assert_eq!(settled.len(), count.min(all.settled().count()));
+            // The closest nodes, up to ties
+            let expected: Vec<_> = all.settled().take(count).map(|(_, d)| d).collect();
+            assert_eq!(settled.iter().map(|&(_, d)| d).collect::<Vec<_>>(), expected);
+            for (node, d) in settled {
+                assert_eq!(all.distance_to(node), Some(d));
+            }
+
+            let unbounded = dijkstra_bounded(&graph, source, None, None);
+            assert_eq!(unbounded.settled().collect::<Vec<_>>(), all.settled().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_path_masked() {
+        //   0 --1--> 1 --1--> 2 --1--> 3
+        //   |        ^                 ^
+        //   +---5----+-------9---------+
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 1, 5), (0, 3, 9), (1, 3, 4)]);
+        let path = dijkstra_path_masked(&graph, 0, 3, &[], |_, _| false).unwrap();
+        assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+
+        // Blocking node 2 on the shortest path
+        let path = dijkstra_path_masked(&graph, 0, 3, &[false, false, true], |_, _| false).unwrap();
+        assert_eq!((path.cost, path.nodes.clone()), (5, vec![0, 1, 3]));
+        assert_path(&graph, &path, 0, 3);
+
+        // Blocking edge 0 -> 1 as well, including its parallel edge
+        let path = dijkstra_path_masked(&graph, 0, 3, &[false, false, true], |from, to| (from, to) == (0, 1)).unwrap();
+        assert_eq!((path.cost, path.nodes), (9, vec![0, 3]));
+
+        assert_eq!(dijkstra_path_masked(&graph, 0, 3, &[false, false, false, true], |_, _| false), None);
+        assert_eq!(dijkstra_path_masked(&graph, 0, 3, &[true], |_, _| false), None);
+    }
+
+    #[test]
+    fn test_dijkstra_multi() {
+        let mut rng = XorShift::new(23);
+        for _ in 0..500 {
+            let graph = random_graph(&mut rng, 20, 60, 10);
+            let n = graph.node_count();
+            let sources: Vec<_> = (0..1 + rng.below(4)).map(|_| (rng.below(n), rng.below(10))).collect();
+            let targets: Vec<_> = (0..1 + rng.below(4)).map(|_| rng.below(n)).collect();
+
+            let expected = sources.iter()
+                .flat_map(|&(source, offset)| targets.iter().map(move |&target| (source, offset, target)))
+                .filter_map(|(source, offset, target)| dijkstra(&graph, source, target).map(|cost| cost + offset))
+                .min();
+            let best = dijkstra_multi_offsets(&graph, &sources, &targets);
+            assert_eq!(best.as_ref().map(|best| best.cost), expected);
+            if let Some(best) = best {
+                assert!(targets.contains(&best.target));
+                let offset = sources.iter()
+                    .filter(|&&(source, _)| source == best.source)
+                    .map(|&(_, offset)| offset)
+                    .min()
+                    .unwrap();
+                assert_eq!(best.cost, offset + best.path.cost);
+                assert_path(&graph, &best.path, best.source, best.target);
+            }
+
+            let sources: Vec<_> = sources.iter().map(|&(source, _)| source).collect();
+            let table = dijkstra_table(&graph, &sources, &targets);
+            let table_inv = dijkstra_table(&graph, &targets, &sources);
+            for (i, &source) in sources.iter().enumerate() {
+                for (j, &target) in targets.iter().enumerate() {
+                    assert_eq!(table[i][j], dijkstra(&graph, source, target));
+                    assert_eq!(table_inv[j][i], dijkstra(&graph, target, source));
+                }
+            }
+            assert_eq!(dijkstra_table(&graph, &sources, &[]), vec![Vec::new(); sources.len()]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "inconsistent heuristic")]
+    #[cfg(debug_assertions)]
+    fn test_astar_inconsistent() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1)]);
+        astar_shortest_path(&graph, 0, 2, &|node: usize| [2, 5, 0][node]);
+    }
 
     #[test]
     fn test_adjency_list_inversion() {
-        let graph = vec![
+        let graph = AdjacencyList::from(vec![
             // Node 0
             vec![Edge { node: 2, cost: 10 },
                  Edge { node: 1, cost: 1 }],
@@ -369,8 +1763,8 @@ mod tests {
             vec![Edge { node: 0, cost: 7 },
                  Edge { node: 4, cost: 2 }],
             // Node 4
-            vec![]];
-        let res = vec![
+            vec![]]);
+        let res = AdjacencyList::from(vec![
             // Node 0
             vec![Edge { node: 3, cost: 7 }],
             // Node 1
@@ -384,7 +1778,7 @@ mod tests {
             // Node 4
             vec![Edge { node: 2, cost: 1 },
                  Edge { node: 3, cost: 2 }]
-        ];
-        assert_eq!(invert_adjecency_list(&graph), res);
+        ]);
+        assert_eq!(CsrGraph::transpose_of(&graph), CsrGraph::from(&res));
     }
 }
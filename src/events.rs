@@ -0,0 +1,351 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::csr::CsrGraph;
+use crate::dijkstra::{Frontier, ShortestPath, State};
+use crate::graph::{Edge, Graph};
+use crate::weight::Weight;
+
+
+/// Which search of a bidirectional [`DijkstraIter`] an event belongs to.
+///
+/// [`DijkstraIter`]: struct.DijkstraIter.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    /// Search from `start` along the edges, the only one of a unidirectional search
+    Forward,
+    /// Search from `goal` against the edges
+    Backward,
+}
+
+
+/// Step of Dijkstra's algorithm, as yielded by [`DijkstraIter`].
+///
+/// Every entry popped from the frontier gives a `Pop`, followed by either `SkipStale` or
+/// `Settle`. Settling a node is followed by a `Relax` for every edge leading to a cheaper way to
+/// another node.
+///
+/// [`DijkstraIter`]: struct.DijkstraIter.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event<W> {
+    /// `node` was taken from the frontier with the given `cost`.
+    Pop { direction: Direction, node: usize, cost: W },
+    /// The entry just popped is outdated, a cheaper way to `node` was found after pushing it.
+    SkipStale { direction: Direction, node: usize, cost: W },
+    /// The distance of `node` is final.
+    Settle { direction: Direction, node: usize, cost: W },
+    /// The edge from `from` to `to` improved the distance of `to` from `old` to `new`, and `to`
+    /// was pushed to the frontier.
+    Relax { direction: Direction, from: usize, to: usize, old: Option<W>, new: W },
+    /// The shortest path is known, with its `cost` and the node where both searches `meet`, or
+    /// the goal itself. This is the last event.
+    GoalReached { cost: W, meet: usize },
+}
+
+
+/// One direction of the search.
+struct Search<W> {
+    direction: Direction,
+    heap: BinaryHeap<State<W>>,
+    dist: Vec<Option<W>>,
+    pred: Vec<Option<Edge<W>>>,
+}
+
+impl<W: Weight> Search<W> {
+    fn new(direction: Direction, n: usize, source: usize) -> Search<W> {
+        let mut search = Search { direction, heap: BinaryHeap::new(), dist: vec![None; n], pred: vec![None; n] };
+        search.dist[source] = Some(W::zero());
+        Frontier::push(&mut search.heap, W::zero(), source);
+        search
+    }
+}
+
+
+/// Dijkstra's algorithm as an iterator of [`Event`]s, to follow it step by step.
+///
+/// Runs the same searches as [`dijkstra_path`] and [`dijkstra_bidir_path`], but instead of
+/// running to the end, every call to `next` advances them by one entry of a frontier and yields
+/// what happened. Once [`Event::GoalReached`] was yielded, the shortest path is available from
+/// [`path`]. If the goal is unreachable, the iterator ends without it.
+///
+/// # Example
+/// ```
+/// use vasa::events::{DijkstraIter, Direction, Event};
+/// use vasa::graph::AdjacencyList;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let mut events = DijkstraIter::new(&graph, 0, 2);
+/// let forward = Direction::Forward;
+/// assert_eq!(events.next(), Some(Event::Pop { direction: forward, node: 0, cost: 0 }));
+/// assert_eq!(events.next(), Some(Event::Settle { direction: forward, node: 0, cost: 0 }));
+/// assert_eq!(events.next(), Some(Event::Relax { direction: forward, from: 0, to: 1, old: None, new: 1 }));
+/// assert_eq!(events.next(), Some(Event::Relax { direction: forward, from: 0, to: 2, old: None, new: 4 }));
+/// assert_eq!(events.next(), Some(Event::Pop { direction: forward, node: 1, cost: 1 }));
+/// assert_eq!(events.next(), Some(Event::Settle { direction: forward, node: 1, cost: 1 }));
+/// assert_eq!(events.next(), Some(Event::Relax { direction: forward, from: 1, to: 2, old: Some(4), new: 3 }));
+/// // The outdated entry of node 2 with cost 4 is never popped, as the goal comes first
+/// let rest: Vec<_> = events.by_ref().collect();
+/// assert_eq!(rest.last(), Some(&Event::GoalReached { cost: 3, meet: 2 }));
+/// assert_eq!(events.path().unwrap().nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`Event`]: enum.Event.html
+/// [`Event::GoalReached`]: enum.Event.html#variant.GoalReached
+/// [`dijkstra_path`]: ../dijkstra/fn.dijkstra_path.html
+/// [`dijkstra_bidir_path`]: ../dijkstra/fn.dijkstra_bidir_path.html
+/// [`path`]: #method.path
+pub struct DijkstraIter<'a, G: Graph> {
+    graph: &'a G,
+    /// Transpose of `graph` for the backward search, unless it has reverse neighbors
+    transpose: Option<CsrGraph<G::Weight>>,
+    goal: usize,
+    forward: Search<G::Weight>,
+    backward: Option<Search<G::Weight>>,
+    /// Best path found so far, as cost and node where it passes from one search to the other
+    best: Option<(G::Weight, usize)>,
+    /// Events of the last step not yielded yet
+    events: VecDeque<Event<G::Weight>>,
+    done: bool,
+    reached: bool,
+}
+
+impl<'a, W, G> DijkstraIter<'a, G>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    /// Creating a new search from `start` to `goal`, like [`dijkstra_path`].
+    ///
+    /// [`dijkstra_path`]: ../dijkstra/fn.dijkstra_path.html
+    pub fn new(graph: &'a G, start: usize, goal: usize) -> DijkstraIter<'a, G> {
+        DijkstraIter {
+            graph,
+            transpose: None,
+            goal,
+            forward: Search::new(Direction::Forward, graph.node_count(), start),
+            backward: None,
+            best: None,
+            events: VecDeque::new(),
+            done: false,
+            reached: false,
+        }
+    }
+
+    /// Creating a new bidirectional search from `start` to `goal`, like [`dijkstra_bidir_path`].
+    ///
+    /// [`dijkstra_bidir_path`]: ../dijkstra/fn.dijkstra_bidir_path.html
+    pub fn bidirectional(graph: &'a G, start: usize, goal: usize) -> DijkstraIter<'a, G> {
+        let transpose = if graph.reverse_neighbors(goal).is_some() {
+            None
+        } else {
+            Some(CsrGraph::transpose_of(graph))
+        };
+        DijkstraIter {
+            transpose,
+            backward: Some(Search::new(Direction::Backward, graph.node_count(), goal)),
+            best: if start == goal { Some((W::zero(), start)) } else { None },
+            ..DijkstraIter::new(graph, start, goal)
+        }
+    }
+
+    /// Shortest path, once [`Event::GoalReached`] was yielded.
+    ///
+    /// [`Event::GoalReached`]: enum.Event.html#variant.GoalReached
+    pub fn path(&self) -> Option<ShortestPath<W>> {
+        if !self.reached {
+            return None;
+        }
+        let (cost, meet) = self.best?;
+        let pred_b = self.backward.as_ref().map_or(&[][..], |search| &search.pred);
+        Some(ShortestPath::stitch(cost, meet, &self.forward.pred, pred_b))
+    }
+
+    /// Ending the search, reaching the goal if a path was found.
+    fn finish(&mut self) {
+        self.done = true;
+        if let Some((cost, meet)) = self.best {
+            self.reached = true;
+            self.events.push_back(Event::GoalReached { cost, meet });
+        }
+    }
+
+    /// Advancing the search by one entry of a frontier, queuing the events.
+    fn step(&mut self) {
+        let top_f = self.forward.heap.peek_min();
+        let forward = match &mut self.backward {
+            None => true,
+            Some(backward) => {
+                let (top_f, top_b) = match (top_f, backward.heap.peek_min()) {
+                    (Some(top_f), Some(top_b)) => (top_f, top_b),
+                    _ => return self.finish(),
+                };
+                // No path through the frontiers can be shorter anymore
+                if self.best.is_some_and(|(cost, _)| top_f.saturating_add(top_b) >= cost) {
+                    return self.finish();
+                }
+                top_f <= top_b
+            }
+        };
+
+        let (search, other) = match (&mut self.backward, forward) {
+            (Some(backward), false) => (backward, Some(&self.forward)),
+            (backward, _) => (&mut self.forward, backward.as_ref()),
+        };
+        let direction = search.direction;
+        let (cost, node) = match search.heap.pop_min() {
+            Some(entry) => entry,
+            None => return self.finish(),
+        };
+        self.events.push_back(Event::Pop { direction, node, cost });
+        if Some(cost) > search.dist[node] {
+            self.events.push_back(Event::SkipStale { direction, node, cost });
+            return;
+        }
+        self.events.push_back(Event::Settle { direction, node, cost });
+        if other.is_none() && node == self.goal {
+            self.best = Some((cost, node));
+            return self.finish();
+        }
+
+        let edges: Vec<_> = match (direction, &self.transpose) {
+            (Direction::Forward, _) => self.graph.neighbors(node).collect(),
+            (Direction::Backward, Some(transpose)) => transpose.neighbors(node).collect(),
+            (Direction::Backward, None) => {
+                self.graph.reverse_neighbors(node).expect("graph has reverse neighbors").collect()
+            }
+        };
+        for edge in edges {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            let new = match cost.checked_add(edge.cost) {
+                Some(new) => new,
+                None => continue,
+            };
+            let old = search.dist[edge.node];
+            if old.is_none_or(|old| new < old) {
+                Frontier::push(&mut search.heap, new, edge.node);
+                search.dist[edge.node] = Some(new);
+                search.pred[edge.node] = Some(Edge { node, cost: edge.cost });
+                self.events.push_back(Event::Relax { direction, from: node, to: edge.node, old, new });
+
+                // The other search has been here already, so we found a path
+                let total = other.and_then(|other| other.dist[edge.node]).and_then(|d| new.checked_add(d));
+                if let Some(total) = total {
+                    if self.best.is_none_or(|(best, _)| total < best) {
+                        self.best = Some((total, edge.node));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<W, G> Iterator for DijkstraIter<'_, G>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    type Item = Event<W>;
+
+    fn next(&mut self) -> Option<Event<W>> {
+        while self.events.is_empty() && !self.done {
+            self.step();
+        }
+        self.events.pop_front()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::{dijkstra, dijkstra_all};
+    use crate::graph::AdjacencyList;
+    use crate::matrix::DistanceMatrix;
+    use crate::testutil::{random_graph, XorShift};
+
+    // Check the events of one search against the distances of Dijkstra's algorithm.
+    fn check<G: Graph<Weight = usize>>(graph: &G, start: usize, goal: usize, bidirectional: bool) {
+        let mut iter = if bidirectional {
+            DijkstraIter::bidirectional(graph, start, goal)
+        } else {
+            DijkstraIter::new(graph, start, goal)
+        };
+        let from_start = dijkstra_all(graph, start);
+        let to_goal = dijkstra_all(&CsrGraph::transpose_of(graph), goal);
+        let distance = |direction, node| match direction {
+            Direction::Forward => from_start.distance_to(node),
+            Direction::Backward => to_goal.distance_to(node),
+        };
+
+        let mut settled = vec![false; 2 * graph.node_count()];
+        let mut popped = None;
+        let mut reached = None;
+        for event in iter.by_ref() {
+            assert_eq!(reached, None, "event after reaching the goal");
+            match event {
+                Event::Pop { direction, node, cost } => {
+                    assert_eq!(popped, None);
+                    popped = Some((direction, node, cost));
+                }
+                Event::SkipStale { direction, node, cost } => {
+                    assert_eq!(popped.take(), Some((direction, node, cost)));
+                    assert!(Some(cost) > distance(direction, node));
+                }
+                Event::Settle { direction, node, cost } => {
+                    assert_eq!(popped.take(), Some((direction, node, cost)));
+                    assert_eq!(Some(cost), distance(direction, node));
+                    let i = node + graph.node_count() * (direction == Direction::Backward) as usize;
+                    assert!(!settled[i], "settled twice");
+                    settled[i] = true;
+                }
+                Event::Relax { old, new, .. } => {
+                    assert!(popped.is_none());
+                    assert!(old.is_none_or(|old| new < old));
+                }
+                Event::GoalReached { cost, .. } => reached = Some(cost),
+            }
+        }
+
+        assert_eq!(reached, dijkstra(graph, start, goal));
+        let path = iter.path();
+        assert_eq!(path.as_ref().map(|path| path.cost), reached);
+        if let Some(path) = path {
+            assert_eq!((path.nodes[0], path.nodes[path.nodes.len() - 1]), (start, goal));
+            assert_eq!(path.edges.iter().map(|edge| edge.cost).sum::<usize>(), path.cost);
+        }
+    }
+
+    #[test]
+    fn like_dijkstra() {
+        let mut rng = XorShift::new(20);
+        for _ in 0..300 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            check(&graph, start, goal, false);
+            check(&graph, start, goal, true);
+            // Backward search on reverse neighbors
+            check(&DistanceMatrix::from(&graph), start, goal, true);
+        }
+    }
+
+    #[test]
+    fn bidirectional() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 1)]);
+        let events: Vec<_> = DijkstraIter::bidirectional(&graph, 0, 2).collect();
+        assert_eq!(events[..3], [
+            Event::Pop { direction: Direction::Forward, node: 0, cost: 0 },
+            Event::Settle { direction: Direction::Forward, node: 0, cost: 0 },
+            Event::Relax { direction: Direction::Forward, from: 0, to: 1, old: None, new: 1 },
+        ]);
+        // The backward search meets the forward one at node 1
+        assert_eq!(events[5], Event::Relax { direction: Direction::Backward, from: 2, to: 1, old: None, new: 1 });
+        assert_eq!(events.last(), Some(&Event::GoalReached { cost: 2, meet: 1 }));
+    }
+
+    #[test]
+    fn unreachable() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (2, 1, 1)]);
+        let mut iter = DijkstraIter::new(&graph, 0, 2);
+        assert_eq!(iter.by_ref().count(), 5);
+        assert_eq!(iter.path(), None);
+        assert_eq!(DijkstraIter::bidirectional(&graph, 0, 2).last(),
+                   Some(Event::Settle { direction: Direction::Backward, node: 2, cost: 0 }));
+    }
+}
@@ -53,7 +53,13 @@ impl<T> BoundedFIFO<T>
     /// assert!(fifo.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.b.len() == 0
+        // an empty queue always has both indices at the end of the used memory
+        self.h == self.t && self.t == self.b.len()
+    }
+
+    /// Test if all used memory is occupied by elements of the queue.
+    fn is_full(&self) -> bool {
+        self.h == self.t && self.t < self.b.len()
     }
 
     /// Get reference to first element in queue.
@@ -72,7 +78,7 @@ impl<T> BoundedFIFO<T>
     /// # Panics
     /// If the queue is empty.
     pub fn first(&self) -> &T {
-        assert!(self.b.len() > 0);
+        assert!(!self.is_empty());
         &self[self.h]
     }
 
@@ -87,7 +93,14 @@ impl<T> BoundedFIFO<T>
     pub fn size(&self) -> usize {
         // self.b.len() is not sufficiently accurate
         // since some elements could have been 'deleted' already
-        (self.t - self.h) % self.b.capacity()
+        if self.is_empty() {
+            0
+        } else if self.h < self.t {
+            self.t - self.h
+        } else {
+            // wrapped around the end of the used memory
+            self.b.len() - self.h + self.t
+        }
     }
 
     /// Adding a new element to the queue.
@@ -101,20 +114,25 @@ impl<T> BoundedFIFO<T>
     /// fifo.push_back(2);
     /// ```
     pub fn push_back(&mut self, elem: T) {
-        if self.t == self.b.len() {
-            self.b.push(elem);
-        } else if self.h == self.t {
+        if self.is_empty() {
+            // start over at the front of the used memory
+            self.h = 0;
+            self.t = 0;
+        } else if self.is_full() {
             self.b.rotate_left(self.h); // move the t first elements to the back
             self.h = 0;                 // first element is 0 again
             self.t = self.b.len();      // last element is going to be at current length
+        } else if self.t == self.b.len() && self.h > 0 {
+            // wrap around, the front has been freed
+            self.t = 0;
+        }
+
+        if self.t == self.b.len() {
             self.b.push(elem);
         } else {
             self.b[self.t] = elem;
         }
-        if self.t + 1 == self.b.capacity() && self.h == 0 {
-            self.b.reserve(self.b.capacity());
-        }
-        self.t = (self.t + 1) % dbg!(self.b.capacity());
+        self.t += 1;
     }
 
     /// Pop the first element from the queue.
@@ -130,13 +148,23 @@ impl<T> BoundedFIFO<T>
     /// fifo.push_back(2);
     /// assert_eq!(fifo.pop_front(), Some(1));
     /// assert_eq!(fifo.pop_front(), Some(2));
+    /// assert_eq!(fifo.pop_front(), None);
     /// ```
     pub fn pop_front(&mut self) -> Option<T> {
         if self.is_empty() {
             None
         } else {
             let res = self[self.h];
-            self.h = (self.h + 1) % self.b.capacity();
+            self.h += 1;
+            if self.h == self.b.len() && self.t < self.b.len() {
+                // wrap around to the elements at the front
+                self.h = 0;
+            }
+            if self.h == self.t {
+                // that was the last element
+                self.h = self.b.len();
+                self.t = self.b.len();
+            }
             Some(res)
         }
     }
@@ -230,6 +258,50 @@ mod tests {
                    });
     }
 
+    #[test]
+    fn pop_empty() {
+        let mut fifo = BoundedFIFO::<i32>::new(2);
+        fifo.push_back(14);
+        fifo.pop_front();
+        assert!(fifo.is_empty());
+        assert_eq!(fifo.size(), 0);
+        assert_eq!(fifo.pop_front(), None);
+    }
+
+    #[test]
+    fn reuse_memory() {
+        let mut fifo = BoundedFIFO::<i32>::new(2);
+        for i in 0..100 {
+            fifo.push_back(i);
+            fifo.push_back(i + 1);
+            assert_eq!(fifo.pop_front(), Some(i));
+            assert_eq!(fifo.pop_front(), Some(i + 1));
+        }
+        assert_eq!(fifo.b.len(), 2);
+    }
+
+    #[test]
+    fn like_vecdeque() {
+        use std::collections::VecDeque;
+        use crate::testutil::XorShift;
+        let mut rng = XorShift::new(0x2545f491);
+        for n in 1..50 {
+            let mut fifo = BoundedFIFO::<u64>::new(n % 7);
+            let mut deque = VecDeque::new();
+            for _ in 0..200 {
+                let x = rng.next();
+                if x % 5 < 3 {
+                    fifo.push_back(x);
+                    deque.push_back(x);
+                } else {
+                    assert_eq!(fifo.pop_front(), deque.pop_front());
+                }
+                assert_eq!(fifo.size(), deque.len());
+                assert_eq!(fifo.is_empty(), deque.is_empty());
+            }
+        }
+    }
+
     #[test]
     fn overwrite() {
         let mut fifo = BoundedFIFO::<i32>::new(4);
@@ -0,0 +1,332 @@
+use std::ops::Index;
+
+
+/// Outgoing edge of a node, pointing to `node` with a given `cost`.
+///
+/// Each node is represented as an `usize`, for a shorter implementation. The cost can be any
+/// [`Weight`], and defaults to `usize`.
+///
+/// [`Weight`]: ../weight/trait.Weight.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Edge<W = usize> {
+    /// Node this edge points to
+    pub node: usize,
+    /// Cost of traversing this edge
+    pub cost: W,
+}
+
+
+/// Directed graph whose outgoing edges can be listed node by node, as needed by the searches of
+/// this crate.
+///
+/// Implemented by [`AdjacencyList`], [`CsrGraph`], the dense [`DistanceMatrix`] and the
+/// [`ImplicitGraph`] of a closure, so every search is written once and runs on all of them.
+///
+/// Some representations can also list the incoming edges of a node cheaply, which
+/// bidirectional searches use instead of building a transposed copy of the graph.
+///
+/// # Example
+/// ```
+/// use vasa::csr::CsrGraph;
+/// use vasa::graph::{AdjacencyList, Graph};
+/// fn out_degree<G: Graph>(graph: &G, node: usize) -> usize {
+///     graph.neighbors(node).count()
+/// }
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (0, 2, 1)]);
+/// assert_eq!(out_degree(&graph, 0), 2);
+/// assert_eq!(out_degree(&CsrGraph::from(&graph), 0), 2);
+/// ```
+///
+/// [`AdjacencyList`]: struct.AdjacencyList.html
+/// [`CsrGraph`]: ../csr/struct.CsrGraph.html
+/// [`DistanceMatrix`]: ../matrix/struct.DistanceMatrix.html
+/// [`ImplicitGraph`]: struct.ImplicitGraph.html
+pub trait Graph {
+    /// Type of the edge costs.
+    type Weight: Copy;
+
+    /// Return number of nodes, which are `0..node_count()`.
+    fn node_count(&self) -> usize;
+
+    /// Iterate over the outgoing edges of `node`.
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<Self::Weight>>;
+
+    /// Iterate over the incoming edges of `node`, each pointing back to the node it comes from.
+    ///
+    /// Returns `None` if the representation does not support this, which is the default. Either
+    /// all nodes or none have reverse neighbors.
+    fn reverse_neighbors(&self, node: usize) -> Option<impl Iterator<Item = Edge<Self::Weight>>> {
+        let _ = node;
+        None::<std::iter::Empty<Edge<Self::Weight>>>
+    }
+}
+
+
+/// Graph with the incoming edges of `G` as outgoing ones, for graphs with reverse neighbors.
+///
+/// # Panics
+/// On listing neighbors, if `G` has no reverse neighbors.
+pub(crate) struct Reversed<'a, G>(pub &'a G);
+
+impl<G: Graph> Graph for Reversed<'_, G> {
+    type Weight = G::Weight;
+
+    fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<G::Weight>> {
+        self.0.reverse_neighbors(node).expect("graph has reverse neighbors")
+    }
+}
+
+
+/// Graph given by a closure listing the outgoing edges of each node.
+///
+/// Nothing is stored, edges are generated whenever a search asks for them. This suits graphs
+/// following a simple rule, such as grids or state spaces, which would be wasteful to build.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra;
+/// use vasa::graph::{Edge, ImplicitGraph};
+/// // Each node `i` of a line leads to `i + 1` with cost 1, and jumps to `2 * i` with cost 3
+/// let graph = ImplicitGraph::new(100, |i: usize| {
+///     vec![(i + 1, 1), (2 * i, 3)].into_iter()
+///         .filter(|&(node, _)| node < 100)
+///         .map(|(node, cost)| Edge { node, cost })
+/// });
+/// assert_eq!(dijkstra(&graph, 1, 64), Some(15));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ImplicitGraph<F> {
+    n: usize,
+    neighbors: F,
+}
+
+impl<F> ImplicitGraph<F> {
+    /// Creating a new `ImplicitGraph` with the nodes `0..n`, whose outgoing edges are
+    /// `neighbors(node)`.
+    pub fn new(n: usize, neighbors: F) -> ImplicitGraph<F> {
+        ImplicitGraph { n, neighbors }
+    }
+}
+
+impl<W, F, I> Graph for ImplicitGraph<F>
+    where W: Copy,
+          F: Fn(usize) -> I,
+          I: IntoIterator<Item = Edge<W>>
+{
+    type Weight = W;
+
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<W>> {
+        (self.neighbors)(node).into_iter()
+    }
+}
+
+
+/// Directed graph, represented as an adjacency list of [`Edge`]s.
+///
+/// Each index, corresponding to a node value, has a list of outgoing edges.
+///
+/// [`Edge`]: struct.Edge.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyList<W = usize> {
+    adj: Vec<Vec<Edge<W>>>,
+}
+
+impl<W> From<Vec<Vec<Edge<W>>>> for AdjacencyList<W> {
+    fn from(adj: Vec<Vec<Edge<W>>>) -> AdjacencyList<W> {
+        for Edge { node, .. } in adj.iter().flatten() {
+            assert!(*node < adj.len(), "edge to unknown node {}", node);
+        }
+        AdjacencyList { adj }
+    }
+}
+
+impl<W> Default for AdjacencyList<W> {
+    fn default() -> AdjacencyList<W> {
+        AdjacencyList::new()
+    }
+}
+
+impl<W> Index<usize> for AdjacencyList<W> {
+    type Output = [Edge<W>];
+
+    fn index(&self, node: usize) -> &[Edge<W>] {
+        &self.adj[node]
+    }
+}
+
+impl<W> AdjacencyList<W> {
+    /// Creating a new and empty `AdjacencyList`.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::graph::AdjacencyList;
+    /// let graph: AdjacencyList = AdjacencyList::new();
+    /// assert_eq!(graph.node_count(), 0);
+    /// ```
+    pub fn new() -> AdjacencyList<W> {
+        AdjacencyList { adj: Vec::new() }
+    }
+
+    /// Creating a new and empty `AdjacencyList` with space for `n` nodes.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::graph::AdjacencyList;
+    /// let graph: AdjacencyList<u32> = AdjacencyList::with_capacity(16);
+    /// assert_eq!(graph.node_count(), 0);
+    /// ```
+    pub fn with_capacity(n: usize) -> AdjacencyList<W> {
+        AdjacencyList { adj: Vec::with_capacity(n) }
+    }
+
+    /// Building a `AdjacencyList` from `(from, to, cost)` triples.
+    ///
+    /// The graph gets as many nodes as needed for the largest node mentioned.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::graph::AdjacencyList;
+    /// let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (1, 2, 3)]);
+    /// assert_eq!(graph.node_count(), 3);
+    /// assert_eq!(graph.edge_count(), 2);
+    /// ```
+    pub fn from_edges<I>(edges: I) -> AdjacencyList<W>
+        where I: IntoIterator<Item = (usize, usize, W)>
+    {
+        let mut graph = AdjacencyList::new();
+        for (from, to, cost) in edges {
+            while graph.node_count() <= from.max(to) {
+                graph.add_node();
+            }
+            graph.add_edge(from, to, cost);
+        }
+        graph
+    }
+
+    /// Adding a new node without any edges, returning its index.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::graph::AdjacencyList;
+    /// let mut graph: AdjacencyList = AdjacencyList::new();
+    /// assert_eq!(graph.add_node(), 0);
+    /// assert_eq!(graph.add_node(), 1);
+    /// ```
+    pub fn add_node(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.adj.len() - 1
+    }
+
+    /// Adding a directed edge from `from` to `to` with the given `cost`.
+    ///
+    /// # Example
+    /// ```
+    /// use vasa::graph::{AdjacencyList, Edge};
+    /// let mut graph = AdjacencyList::new();
+    /// let a = graph.add_node();
+    /// let b = graph.add_node();
+    /// graph.add_edge(a, b, 7);
+    /// assert_eq!(graph.neighbors(a), &[Edge { node: b, cost: 7 }]);
+    /// ```
+    ///
+    /// # Panics
+    /// If either node does not exist yet.
+    pub fn add_edge(&mut self, from: usize, to: usize, cost: W) {
+        assert!(from < self.adj.len(), "unknown node {}", from);
+        assert!(to < self.adj.len(), "unknown node {}", to);
+        self.adj[from].push(Edge { node: to, cost });
+    }
+
+    /// Return number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Return number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(Vec::len).sum()
+    }
+
+    /// Get outgoing edges of `node`.
+    ///
+    /// # Panics
+    /// If the node does not exist.
+    pub fn neighbors(&self, node: usize) -> &[Edge<W>] {
+        &self.adj[node]
+    }
+}
+
+impl<W: Copy> Graph for AdjacencyList<W> {
+    type Weight = W;
+
+    fn node_count(&self) -> usize {
+        AdjacencyList::node_count(self)
+    }
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<W>> {
+        AdjacencyList::neighbors(self, node).iter().copied()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let graph: AdjacencyList = AdjacencyList::new();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn build() {
+        let mut graph = AdjacencyList::with_capacity(2);
+        let a = graph.add_node();
+        let b = graph.add_node();
+        graph.add_edge(a, b, 3);
+        graph.add_edge(b, a, 5);
+        assert_eq!(graph,
+                   AdjacencyList {
+                       adj: vec![
+                           vec![Edge { node: 1, cost: 3 }],
+                           vec![Edge { node: 0, cost: 5 }],
+                       ],
+                   });
+    }
+
+    #[test]
+    fn from_edges() {
+        let graph = AdjacencyList::from_edges(vec![(0, 3, 1), (2, 1, 4)]);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph[0], [Edge { node: 3, cost: 1 }]);
+        assert_eq!(graph[2], [Edge { node: 1, cost: 4 }]);
+        assert!(graph[1].is_empty());
+    }
+
+    #[test]
+    fn implicit() {
+        // Cycle of 5 nodes, each leading to the next two
+        let graph = ImplicitGraph::new(5, |node| (1..=2).map(move |step| Edge { node: (node + step) % 5, cost: step }));
+        assert_eq!(graph.node_count(), 5);
+        assert!(graph.neighbors(4).eq(vec![Edge { node: 0, cost: 1 }, Edge { node: 1, cost: 2 }]));
+        assert!(graph.reverse_neighbors(0).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn edge_to_unknown_node() {
+        let mut graph: AdjacencyList = AdjacencyList::new();
+        let a = graph.add_node();
+        graph.add_edge(a, 1, 3);
+    }
+}
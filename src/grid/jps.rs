@@ -0,0 +1,261 @@
+use std::f64::consts::SQRT_2;
+
+use crate::dijkstra::ShortestPath;
+use crate::graph::Edge;
+use crate::heuristic::Heuristic;
+use crate::indexed_heap::IndexedHeap;
+use crate::weight::OrderedFloat;
+use super::{Connectivity, GridGraph, Octile, DIRECTIONS};
+
+
+/// Jump Point Search, A* on a grid expanding only the cells where an optimal path may turn.
+///
+/// On a grid where all passable cells cost the same, there are many shortest paths of equal
+/// cost, differing only in the order of their moves. Instead of adding every neighbor to the
+/// frontier, the search keeps moving in a straight line ("jumps") until it hits a wall, the goal
+/// or a jump point: a cell next to an obstacle where a shortest path may have to turn. Diagonal
+/// jumps stop wherever a straight jump from them would find a jump point. Only jump points enter
+/// the frontier, which on open maps is a tiny fraction of the cells A* would examine.
+///
+/// The path returned contains every cell passed, like the one of [`astar`].
+///
+/// # Example
+/// ```
+/// use vasa::grid::{jump_point_search, Connectivity, GridGraph};
+/// let mut grid = GridGraph::new(5, 5, Connectivity::Eight);
+/// for y in 0..4 {
+///     grid.block(2, y);
+/// }
+/// let path = jump_point_search(&grid, grid.node(0, 0), grid.node(4, 0)).unwrap();
+/// assert_eq!(path.nodes.len(), 11);
+/// assert!((path.cost.0 - (8.0 + 2.0 * 2f64.sqrt())).abs() < 1e-9);
+/// ```
+///
+/// # Panics
+/// If the grid is not 8-connected, or its passable cells do not all have the same cost.
+///
+/// [`astar`]: fn.astar.html
+pub fn jump_point_search(grid: &GridGraph, start: usize, goal: usize) -> Option<ShortestPath<OrderedFloat<f64>>> {
+    assert_eq!(grid.connectivity(), Connectivity::Eight, "jump point search needs an 8-connected grid");
+    if start == goal {
+        return Some(ShortestPath { cost: OrderedFloat(0.0), nodes: vec![start], edges: Vec::new() });
+    }
+    let (sx, sy) = grid.coords(start);
+    if !grid.passable(sx as isize, sy as isize) {
+        return None;
+    }
+    let unit = grid.uniform_cost().expect("jump point search needs the same cost for all cells");
+
+    let heuristic = Octile::new(grid, goal);
+    let (gx, gy) = grid.coords(goal);
+    let target = (gx as isize, gy as isize);
+
+    // dist[node] = cost of the best path known from `start` to the jump point `node`
+    // parent[node] = jump point that path comes from
+    let mut dist: Vec<Option<f64>> = vec![None; grid.node_count()];
+    let mut parent: Vec<Option<usize>> = vec![None; grid.node_count()];
+    let mut heap = IndexedHeap::new(grid.node_count());
+
+    dist[start] = Some(0.0);
+    heap.push(start, heuristic.estimate(start));
+
+    while let Some((node, _)) = heap.pop() {
+        if node == goal {
+            return Some(unpack(grid, unit, &parent, goal, OrderedFloat(dist[goal].unwrap())));
+        }
+        let cost = dist[node].unwrap();
+        let (x, y) = grid.coords(node);
+        let (x, y) = (x as isize, y as isize);
+
+        for (dx, dy) in directions(grid, x, y, parent[node].map(|p| grid.coords(p))) {
+            let (jx, jy) = match jump(grid, x, y, dx, dy, target) {
+                Some(point) => point,
+                None => continue,
+            };
+            let steps = (jx - x).abs().max((jy - y).abs()) as f64;
+            let length = if dx != 0 && dy != 0 { SQRT_2 } else { 1.0 };
+            let next = cost + steps * length * unit;
+
+            let jump_point = grid.node(jx as usize, jy as usize);
+            if dist[jump_point].is_none_or(|d| next < d) {
+                dist[jump_point] = Some(next);
+                parent[jump_point] = Some(node);
+                heap.push_or_decrease(jump_point, OrderedFloat(next) + heuristic.estimate(jump_point));
+            }
+        }
+    }
+
+    // Goal not reachable
+    None
+}
+
+
+/// Directions worth jumping to from `(x, y)`, reached from the jump point `parent`.
+///
+/// Coming from a parent, cells that a path through the parent reaches at least as cheaply
+/// without passing `(x, y)` are pruned. Moving straight, only the next cell ahead and the cells
+/// to the sides remain, as the latter may have become reachable around an obstacle. Moving
+/// diagonally, the two straight components and the diagonal itself remain.
+fn directions(grid: &GridGraph, x: isize, y: isize, parent: Option<(usize, usize)>) -> Vec<(isize, isize)> {
+    let (px, py) = match parent {
+        Some((px, py)) => (px as isize, py as isize),
+        None => {
+            return DIRECTIONS.iter()
+                .copied()
+                .filter(|&(dx, dy)| grid.step(x, y, dx, dy).is_some())
+                .collect();
+        }
+    };
+    let (dx, dy) = ((x - px).signum(), (y - py).signum());
+
+    let mut directions = Vec::new();
+    if dx != 0 && dy != 0 {
+        let (vertical, horizontal) = (grid.passable(x, y + dy), grid.passable(x + dx, y));
+        if vertical {
+            directions.push((0, dy));
+        }
+        if horizontal {
+            directions.push((dx, 0));
+        }
+        if vertical && horizontal {
+            directions.push((dx, dy));
+        }
+    } else {
+        // Sides to the left and right of the direction of travel
+        let (sx, sy) = (dy.abs(), dx.abs());
+        let ahead = grid.passable(x + dx, y + dy);
+        if ahead {
+            directions.push((dx, dy));
+        }
+        for &side in &[1, -1] {
+            if grid.passable(x + side * sx, y + side * sy) {
+                directions.push((side * sx, side * sy));
+                if ahead {
+                    directions.push((dx + side * sx, dy + side * sy));
+                }
+            }
+        }
+    }
+    directions
+}
+
+
+/// Moving from `(x, y)` in direction `(dx, dy)` until reaching `goal` or a jump point, `None` if
+/// running into a blocked cell first.
+fn jump(grid: &GridGraph, mut x: isize, mut y: isize, dx: isize, dy: isize, goal: (isize, isize))
+    -> Option<(isize, isize)> {
+
+    let diagonal = dx != 0 && dy != 0;
+    loop {
+        // Diagonal moves must not cut corners
+        if diagonal && !(grid.passable(x + dx, y) && grid.passable(x, y + dy)) {
+            return None;
+        }
+        x += dx;
+        y += dy;
+        if !grid.passable(x, y) {
+            return None;
+        }
+        if (x, y) == goal {
+            return Some((x, y));
+        }
+
+        let forced = if diagonal {
+            jump(grid, x, y, dx, 0, goal).is_some() || jump(grid, x, y, 0, dy, goal).is_some()
+        } else {
+            // A side cell is forced if the cell behind it is blocked, so that the shortest way
+            // there leads through `(x, y)`
+            let (sx, sy) = (dy.abs(), dx.abs());
+            [1, -1].iter().any(|&side| {
+                grid.passable(x + side * sx, y + side * sy)
+                    && !grid.passable(x + side * sx - dx, y + side * sy - dy)
+            })
+        };
+        if forced {
+            return Some((x, y));
+        }
+    }
+}
+
+
+/// Path from `start` to `goal`, filling in the cells between consecutive jump points.
+fn unpack(grid: &GridGraph, unit: f64, parent: &[Option<usize>], goal: usize, cost: OrderedFloat<f64>)
+    -> ShortestPath<OrderedFloat<f64>> {
+
+    let mut jump_points = vec![goal];
+    while let Some(prev) = parent[jump_points[jump_points.len() - 1]] {
+        jump_points.push(prev);
+    }
+    jump_points.reverse();
+
+    let mut nodes = vec![jump_points[0]];
+    let mut edges = Vec::new();
+    for pair in jump_points.windows(2) {
+        let (x, y) = grid.coords(pair[0]);
+        let (tx, ty) = grid.coords(pair[1]);
+        let (dx, dy) = ((tx as isize - x as isize).signum(), (ty as isize - y as isize).signum());
+        let length = if dx != 0 && dy != 0 { SQRT_2 } else { 1.0 };
+
+        let (mut x, mut y) = (x as isize, y as isize);
+        while (x, y) != (tx as isize, ty as isize) {
+            x += dx;
+            y += dy;
+            let node = grid.node(x as usize, y as usize);
+            edges.push(Edge { node, cost: OrderedFloat(length * unit) });
+            nodes.push(node);
+        }
+    }
+
+    ShortestPath { cost, nodes, edges }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::grid::astar;
+    use crate::grid::tests::random_grid;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn open_grid() {
+        let grid = GridGraph::new(50, 50, Connectivity::Eight);
+        let path = jump_point_search(&grid, grid.node(0, 0), grid.node(49, 10)).unwrap();
+        assert!((path.cost.0 - (39.0 + 10.0 * SQRT_2)).abs() < 1e-9);
+        assert_eq!(path.nodes.len(), 50);
+    }
+
+    #[test]
+    fn like_astar() {
+        let mut rng = XorShift::new(170);
+        for _ in 0..1000 {
+            let grid = random_grid(&mut rng, Connectivity::Eight, 3, 1);
+            let n = grid.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let expected = astar(&grid, start, goal).map(|path| path.cost.0);
+            let path = jump_point_search(&grid, start, goal);
+            match (path.as_ref().map(|path| path.cost.0), expected) {
+                (Some(cost), Some(expected)) => assert!((cost - expected).abs() < 1e-9),
+                (cost, expected) => assert_eq!(cost, expected),
+            }
+
+            // The path is a walk along the edges of the grid
+            if let Some(path) = path {
+                assert_eq!(path.nodes.first(), Some(&start));
+                assert_eq!(path.nodes.last(), Some(&goal));
+                for (i, edge) in path.edges.iter().enumerate() {
+                    assert!(grid.neighbors(path.nodes[i]).any(|e| e == *edge));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "same cost")]
+    fn non_uniform() {
+        let mut grid = GridGraph::new(3, 3, Connectivity::Eight);
+        grid.set_cost(1, 1, 2.0);
+        jump_point_search(&grid, 0, 8);
+    }
+}
@@ -0,0 +1,330 @@
+use std::f64::consts::SQRT_2;
+
+use crate::dijkstra::{astar_path, ShortestPath};
+use crate::graph::{Edge, Graph};
+use crate::heuristic::{Heuristic, ROUNDING_MARGIN};
+use crate::weight::OrderedFloat;
+
+mod jps;
+pub mod movingai;
+
+pub use self::jps::jump_point_search;
+
+
+/// Moves allowed from a cell of a [`GridGraph`].
+///
+/// [`GridGraph`]: struct.GridGraph.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right, each with length 1.
+    Four,
+    /// Also the diagonals, each with length `sqrt(2)`. A diagonal move is only allowed if both
+    /// cells it passes between are passable, so paths never cut corners.
+    Eight,
+}
+
+/// Offsets of the moves, the four straight ones first.
+const DIRECTIONS: [(isize, isize); 8] = [(1, 0), (0, 1), (-1, 0), (0, -1),
+                                         (1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+
+/// Grid of cells, as a graph computed on the fly instead of stored edge by edge.
+///
+/// The cell in column `x` and row `y` is the node `y * width + x`. Each cell is either blocked,
+/// or passable with a cost. Moving into a cell costs the length of the move times the cost of
+/// that cell, so with all costs 1 the cost of a path is its length.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra;
+/// use vasa::grid::{Connectivity, GridGraph};
+/// use vasa::weight::OrderedFloat;
+/// let mut grid = GridGraph::new(3, 3, Connectivity::Four);
+/// grid.block(1, 0);
+/// grid.block(1, 1);
+/// grid.set_cost(1, 2, 2.0);
+/// // Around the wall, entering the expensive cell once
+/// let cost = dijkstra(&grid, grid.node(0, 0), grid.node(2, 0));
+/// assert_eq!(cost, Some(OrderedFloat(7.0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridGraph {
+    width: usize,
+    height: usize,
+    connectivity: Connectivity,
+    /// cost[y * width + x] = cost of entering the cell, `None` if it is blocked
+    cost: Vec<Option<f64>>,
+}
+
+impl GridGraph {
+    /// Creating a new `GridGraph` of `width` times `height` cells, all passable with cost 1.
+    pub fn new(width: usize, height: usize, connectivity: Connectivity) -> GridGraph {
+        GridGraph { width, height, connectivity, cost: vec![Some(1.0); width * height] }
+    }
+
+    /// Return number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Moves allowed between cells.
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Return number of nodes, which is the number of cells including blocked ones.
+    pub fn node_count(&self) -> usize {
+        self.cost.len()
+    }
+
+    /// Node of the cell at `(x, y)`.
+    ///
+    /// # Panics
+    /// If the cell is outside of the grid.
+    pub fn node(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.width && y < self.height, "unknown cell ({}, {})", x, y);
+        y * self.width + x
+    }
+
+    /// Column and row of the cell of `node`.
+    pub fn coords(&self, node: usize) -> (usize, usize) {
+        (node % self.width, node / self.width)
+    }
+
+    /// Cost of entering the cell at `(x, y)`, `None` if it is blocked.
+    pub fn cost(&self, x: usize, y: usize) -> Option<f64> {
+        self.cost[self.node(x, y)]
+    }
+
+    /// Test if the cell at `(x, y)` is blocked.
+    pub fn is_blocked(&self, x: usize, y: usize) -> bool {
+        self.cost(x, y).is_none()
+    }
+
+    /// Making the cell at `(x, y)` passable with the given `cost`.
+    ///
+    /// # Panics
+    /// If the cost is negative or not finite, or the cell is outside of the grid.
+    pub fn set_cost(&mut self, x: usize, y: usize, cost: f64) {
+        assert!(cost >= 0.0 && cost.is_finite(), "invalid cell cost {}", cost);
+        let node = self.node(x, y);
+        self.cost[node] = Some(cost);
+    }
+
+    /// Blocking the cell at `(x, y)`, removing all moves into and out of it.
+    pub fn block(&mut self, x: usize, y: usize) {
+        let node = self.node(x, y);
+        self.cost[node] = None;
+    }
+
+    /// Cost of the cell at `(x, y)`, `None` if it is blocked or outside of the grid.
+    fn cell(&self, x: isize, y: isize) -> Option<f64> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        self.cost[y as usize * self.width + x as usize]
+    }
+
+    /// Test if the cell at `(x, y)` is inside the grid and passable.
+    fn passable(&self, x: isize, y: isize) -> bool {
+        self.cell(x, y).is_some()
+    }
+
+    /// Edge for moving from `(x, y)` by `(dx, dy)`, if that move is allowed.
+    fn step(&self, x: isize, y: isize, dx: isize, dy: isize) -> Option<Edge<OrderedFloat<f64>>> {
+        let cost = self.cell(x + dx, y + dy)?;
+        if !self.passable(x, y) {
+            return None;
+        }
+        let length = if dx != 0 && dy != 0 {
+            // Squeezing between two blocked cells, or around the corner of one, is not allowed
+            if !self.passable(x + dx, y) || !self.passable(x, y + dy) {
+                return None;
+            }
+            SQRT_2
+        } else {
+            1.0
+        };
+        let node = (y + dy) as usize * self.width + (x + dx) as usize;
+        Some(Edge { node, cost: OrderedFloat(length * cost) })
+    }
+
+    /// Smallest cost of a passable cell, `None` if all are blocked.
+    fn min_cost(&self) -> Option<f64> {
+        self.cost.iter().flatten().copied().reduce(f64::min)
+    }
+
+    /// Cost shared by all passable cells, `None` if they differ.
+    fn uniform_cost(&self) -> Option<f64> {
+        let mut costs = self.cost.iter().flatten();
+        let first = *costs.next()?;
+        if costs.all(|&cost| cost == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+impl Graph for GridGraph {
+    type Weight = OrderedFloat<f64>;
+
+    fn node_count(&self) -> usize {
+        GridGraph::node_count(self)
+    }
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<OrderedFloat<f64>>> {
+        let (x, y) = self.coords(node);
+        let moves = match self.connectivity {
+            Connectivity::Four => &DIRECTIONS[..4],
+            Connectivity::Eight => &DIRECTIONS[..],
+        };
+        moves.iter().filter_map(move |&(dx, dy)| self.step(x as isize, y as isize, dx, dy))
+    }
+}
+
+
+/// Length of the shortest path to the goal on an empty grid, times the smallest cell cost.
+///
+/// That is the octile distance `max(dx, dy) + (sqrt(2) - 1) * min(dx, dy)` on grids with
+/// [`Connectivity::Eight`], and the Manhattan distance `dx + dy` with [`Connectivity::Four`].
+/// Blocked cells only make paths longer, so this is a consistent [`Heuristic`].
+///
+/// # Example
+/// ```
+/// use vasa::grid::{Connectivity, GridGraph, Octile};
+/// use vasa::heuristic::Heuristic;
+/// let grid = GridGraph::new(4, 4, Connectivity::Eight);
+/// let h = Octile::new(&grid, grid.node(3, 3));
+/// let estimate = h.estimate(grid.node(0, 2)).0;
+/// assert!((estimate - (2.0 + 2f64.sqrt())).abs() < 1e-6);
+/// ```
+///
+/// [`Connectivity::Eight`]: enum.Connectivity.html#variant.Eight
+/// [`Connectivity::Four`]: enum.Connectivity.html#variant.Four
+/// [`Heuristic`]: ../heuristic/trait.Heuristic.html
+#[derive(Debug, Clone, Copy)]
+pub struct Octile<'a> {
+    grid: &'a GridGraph,
+    goal: (usize, usize),
+    scale: f64,
+}
+
+impl<'a> Octile<'a> {
+    /// Creating a new `Octile` heuristic towards `goal`.
+    ///
+    /// Takes `O(n)` time to find the smallest cell cost.
+    pub fn new(grid: &'a GridGraph, goal: usize) -> Octile<'a> {
+        let scale = grid.min_cost().unwrap_or(0.0);
+        Octile { grid, goal: grid.coords(goal), scale }
+    }
+
+    fn distance(&self, node: usize) -> f64 {
+        let (x, y) = self.grid.coords(node);
+        let dx = x.abs_diff(self.goal.0) as f64;
+        let dy = y.abs_diff(self.goal.1) as f64;
+        let length = match self.grid.connectivity {
+            Connectivity::Four => dx + dy,
+            Connectivity::Eight => dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy),
+        };
+        self.scale * length
+    }
+}
+
+impl Heuristic<OrderedFloat<f64>> for Octile<'_> {
+    fn estimate(&self, node: usize) -> OrderedFloat<f64> {
+        OrderedFloat(self.distance(node) * ROUNDING_MARGIN)
+    }
+}
+
+
+/// A* search on a grid, guided by the [`Octile`] heuristic.
+///
+/// # Example
+/// ```
+/// use vasa::grid::{astar, Connectivity, GridGraph};
+/// let mut grid = GridGraph::new(3, 3, Connectivity::Eight);
+/// grid.block(1, 1);
+/// let path = astar(&grid, grid.node(0, 0), grid.node(2, 2)).unwrap();
+/// assert_eq!(path.nodes.len(), 5);
+/// assert_eq!(path.cost.0, 4.0);
+/// ```
+///
+/// [`Octile`]: struct.Octile.html
+pub fn astar(grid: &GridGraph, start: usize, goal: usize) -> Option<ShortestPath<OrderedFloat<f64>>> {
+    astar_path(grid, start, goal, &Octile::new(grid, goal))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra;
+    use crate::testutil::XorShift;
+
+    // Random grid with about one in `blocked` cells blocked, and costs in `1..=max_cost`.
+    pub(super) fn random_grid(rng: &mut XorShift, connectivity: Connectivity,
+                              blocked: usize, max_cost: usize) -> GridGraph {
+        let (width, height) = (1 + rng.below(20), 1 + rng.below(20));
+        let mut grid = GridGraph::new(width, height, connectivity);
+        for y in 0..height {
+            for x in 0..width {
+                if rng.below(blocked) == 0 {
+                    grid.block(x, y);
+                } else {
+                    grid.set_cost(x, y, (1 + rng.below(max_cost)) as f64);
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut grid = GridGraph::new(3, 3, Connectivity::Eight);
+        assert_eq!(grid.neighbors(grid.node(1, 1)).count(), 8);
+        assert_eq!(grid.neighbors(grid.node(0, 0)).count(), 3);
+
+        // No diagonal moves around the corner of a blocked cell
+        grid.block(1, 0);
+        let nodes: Vec<_> = grid.neighbors(grid.node(0, 0)).map(|edge| grid.coords(edge.node)).collect();
+        assert_eq!(nodes, vec![(0, 1)]);
+        assert_eq!(grid.neighbors(grid.node(1, 0)).count(), 0);
+
+        grid.set_cost(2, 2, 3.0);
+        let edge = grid.neighbors(grid.node(1, 1)).find(|edge| edge.node == grid.node(2, 2)).unwrap();
+        assert_eq!(edge.cost, OrderedFloat(3.0 * SQRT_2));
+
+        let grid = GridGraph::new(3, 3, Connectivity::Four);
+        assert_eq!(grid.neighbors(grid.node(1, 1)).count(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_cell() {
+        let grid = GridGraph::new(3, 2, Connectivity::Four);
+        grid.node(1, 2);
+    }
+
+    #[test]
+    fn astar_like_dijkstra() {
+        let mut rng = XorShift::new(17);
+        for &connectivity in &[Connectivity::Four, Connectivity::Eight] {
+            for _ in 0..200 {
+                let grid = random_grid(&mut rng, connectivity, 4, 5);
+                let n = grid.node_count();
+                let (start, goal) = (rng.below(n), rng.below(n));
+                let path = astar(&grid, start, goal);
+                assert_eq!(path.as_ref().map(|path| path.cost), dijkstra(&grid, start, goal));
+                if let Some(path) = path {
+                    assert_eq!((path.nodes[0], path.nodes[path.nodes.len() - 1]), (start, goal));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Maps and scenarios of the [MovingAI] grid pathfinding benchmarks.
+//!
+//! A `.map` file has a short header followed by one line per row of the grid:
+//!
+//! ```text
+//! type octile
+//! height 2
+//! width 3
+//! map
+//! ..@
+//! .T.
+//! ```
+//!
+//! Cells `.` and `G` are passable ground, `S` is passable swamp. Out of bounds `@` and `O`, trees
+//! `T` and water `W` are blocked. Maps are 8-connected, with diagonal moves not cutting corners.
+//!
+//! A `.scen` file starts with `version 1`, followed by one tab-separated problem per line: its
+//! bucket, map file, map width and height, start and goal coordinates, and the optimal cost.
+//!
+//! [MovingAI]: https://movingai.com/benchmarks/grids.html
+
+use crate::io::ParseError;
+use super::{Connectivity, GridGraph};
+
+
+/// Parsing the contents of a `.map` file into an 8-connected grid.
+///
+/// The header lines `type`, `height`, `width` and `map` must come in this order. Malformed maps
+/// are rejected with a [`ParseError`] telling the line.
+///
+/// # Example
+/// ```
+/// use vasa::grid::movingai::parse_map;
+/// let grid = parse_map("type octile\nheight 2\nwidth 3\nmap\n..@\n.T.\n").unwrap();
+/// assert_eq!((grid.width(), grid.height()), (3, 2));
+/// assert!(grid.is_blocked(2, 0));
+/// assert!(grid.is_blocked(1, 1));
+/// assert_eq!(grid.cost(0, 1), Some(1.0));
+/// ```
+///
+/// [`ParseError`]: ../../io/struct.ParseError.html
+pub fn parse_map(text: &str) -> Result<GridGraph, ParseError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line.trim_end()));
+    let last = text.lines().count();
+
+    // Header lines `type`, `height`, `width` and `map`, in this order
+    let (number, value) = parse_header(&mut lines, last, "type")?;
+    value.ok_or_else(|| ParseError::new(number, "missing type"))?;
+    let (number, value) = parse_header(&mut lines, last, "height")?;
+    let height = parse_number(number, value.ok_or_else(|| ParseError::new(number, "missing height"))?)?;
+    let (number, value) = parse_header(&mut lines, last, "width")?;
+    let width = parse_number(number, value.ok_or_else(|| ParseError::new(number, "missing width"))?)?;
+    let (number, value) = parse_header(&mut lines, last, "map")?;
+    if let Some(value) = value {
+        return Err(ParseError::new(number, format!("unexpected `{}` after map", value)));
+    }
+
+    let mut grid = GridGraph::new(width, height, Connectivity::Eight);
+    for y in 0..height {
+        let (number, line) = lines.next()
+            .ok_or_else(|| ParseError::new(last, format!("missing row {}", y)))?;
+        if line.chars().count() != width {
+            return Err(ParseError::new(number, format!("expected {} cells, found {}", width, line.chars().count())));
+        }
+        for (x, cell) in line.chars().enumerate() {
+            match cell {
+                '.' | 'G' | 'S' => {}
+                '@' | 'O' | 'T' | 'W' => grid.block(x, y),
+                _ => return Err(ParseError::new(number, format!("unknown cell `{}`", cell))),
+            }
+        }
+    }
+    if let Some((number, _)) = lines.find(|(_, line)| !line.is_empty()) {
+        return Err(ParseError::new(number, format!("more than {} rows", height)));
+    }
+
+    Ok(grid)
+}
+
+
+/// One problem of a `.scen` file: finding a shortest path on a map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// Group of problems with similar optimal cost
+    pub bucket: usize,
+    /// Path of the `.map` file, as given in the scenario
+    pub map: String,
+    /// Width and height of the map
+    pub size: (usize, usize),
+    /// Column and row of the start cell
+    pub start: (usize, usize),
+    /// Column and row of the goal cell
+    pub goal: (usize, usize),
+    /// Cost of a shortest path
+    pub optimal: f64,
+}
+
+
+/// Parsing the contents of a `.scen` file.
+///
+/// # Example
+/// ```
+/// use vasa::grid::movingai::{parse_map, parse_scenarios};
+/// use vasa::grid::astar;
+/// let grid = parse_map("type octile\nheight 2\nwidth 3\nmap\n...\n.@.\n").unwrap();
+/// let scenarios = parse_scenarios("version 1\n0\tsmall.map\t3\t2\t0\t1\t2\t1\t4.00000000\n").unwrap();
+/// let scenario = &scenarios[0];
+/// let start = grid.node(scenario.start.0, scenario.start.1);
+/// let goal = grid.node(scenario.goal.0, scenario.goal.1);
+/// assert_eq!(astar(&grid, start, goal).unwrap().cost.0, scenario.optimal);
+/// ```
+pub fn parse_scenarios(text: &str) -> Result<Vec<Scenario>, ParseError> {
+    let mut scenarios = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if number == 1 {
+            if line.split_whitespace().next() != Some("version") {
+                return Err(ParseError::new(number, "missing version"));
+            }
+            continue;
+        }
+
+        let fields: Vec<_> = line.split('\t').collect();
+        if fields.len() != 9 {
+            return Err(ParseError::new(number, format!("expected 9 fields, found {}", fields.len())));
+        }
+        let optimal = fields[8].parse()
+            .map_err(|_| ParseError::new(number, format!("invalid cost `{}`", fields[8])))?;
+        scenarios.push(Scenario {
+            bucket: parse_number(number, fields[0])?,
+            map: fields[1].to_string(),
+            size: (parse_number(number, fields[2])?, parse_number(number, fields[3])?),
+            start: (parse_number(number, fields[4])?, parse_number(number, fields[5])?),
+            goal: (parse_number(number, fields[6])?, parse_number(number, fields[7])?),
+            optimal,
+        });
+    }
+    Ok(scenarios)
+}
+
+
+/// Next line of a `.map` header, which must start with `keyword`, and the value following it.
+fn parse_header<'a, I>(lines: &mut I, last: usize, keyword: &str) -> Result<(usize, Option<&'a str>), ParseError>
+    where I: Iterator<Item = (usize, &'a str)>
+{
+    let (number, line) = lines.next()
+        .ok_or_else(|| ParseError::new(last, format!("missing `{}` line", keyword)))?;
+    let mut words = line.split_whitespace();
+    if words.next() != Some(keyword) {
+        return Err(ParseError::new(number, format!("expected `{}` line, found `{}`", keyword, line)));
+    }
+    Ok((number, words.next()))
+}
+
+
+fn parse_number(line: usize, value: &str) -> Result<usize, ParseError> {
+    value.parse().map_err(|_| ParseError::new(line, format!("invalid number `{}`", value)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{astar, jump_point_search};
+
+    const MAP: &str = "type octile\nheight 5\nwidth 6\nmap\n\
+                       ......\n\
+                       .@@@@.\n\
+                       ...T..\n\
+                       TT.@.S\n\
+                       G.....\n";
+
+    const SCEN: &str = "version 1\n\
+                        0\ttest.map\t6\t5\t0\t0\t5\t0\t5\n\
+                        1\ttest.map\t6\t5\t0\t4\t5\t2\t6.41421356\n\
+                        1\ttest.map\t6\t5\t0\t2\t4\t2\t8\n";
+
+    #[test]
+    fn scenarios() {
+        let grid = parse_map(MAP).unwrap();
+        let scenarios = parse_scenarios(SCEN).unwrap();
+        assert_eq!(scenarios.len(), 3);
+        for scenario in scenarios {
+            assert_eq!(scenario.size, (grid.width(), grid.height()));
+            let start = grid.node(scenario.start.0, scenario.start.1);
+            let goal = grid.node(scenario.goal.0, scenario.goal.1);
+            let cost = astar(&grid, start, goal).unwrap().cost.0;
+            assert!((cost - scenario.optimal).abs() < 1e-6, "{:?}: {}", scenario, cost);
+            let cost = jump_point_search(&grid, start, goal).unwrap().cost.0;
+            assert!((cost - scenario.optimal).abs() < 1e-6, "{:?}: {}", scenario, cost);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_map("type octile\nheight 1\nwidth 2\nmap\n.x\n").unwrap_err(),
+                   ParseError::new(5, "unknown cell `x`"));
+        assert_eq!(parse_map("type octile\nheight 2\nwidth 2\nmap\n..\n...\n").unwrap_err().line, 6);
+        assert_eq!(parse_map("type octile\nheight 1\nwidth 2\nmap\n..\n..\n").unwrap_err().line, 6);
+        assert_eq!(parse_map("type octile\nheight two\n").unwrap_err(),
+                   ParseError::new(2, "invalid number `two`"));
+        assert_eq!(parse_map("type octile\nheight 1\nwidth 1\n").unwrap_err().message, "missing `map` line");
+        // The header comes in order, and errors are reported where they are found
+        assert_eq!(parse_map("\ntype octile\nheight 1\nwidth 1\nmap\n.\n").unwrap_err(),
+                   ParseError::new(1, "expected `type` line, found ``"));
+        assert_eq!(parse_map("type octile\nwidth 1\nheight 1\nmap\n.\n").unwrap_err(),
+                   ParseError::new(2, "expected `height` line, found `width 1`"));
+        assert_eq!(parse_map("type octile\nheight 1\nwidth\nmap\n.\n").unwrap_err(),
+                   ParseError::new(3, "missing width"));
+        assert_eq!(parse_map("type octile\nheight 1\nmap\n.\n").unwrap_err().line, 3);
+        assert_eq!(parse_scenarios("version 1\n0\ta.map\t1\t1\t0\t0\t0\t0\n").unwrap_err().line, 2);
+        assert_eq!(parse_scenarios("0\ta.map\t1\t1\t0\t0\t0\t0\t0\n").unwrap_err().line, 1);
+    }
+}
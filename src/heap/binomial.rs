@@ -0,0 +1,258 @@
+use super::{Arena, Handle, Links, MeldableHeap};
+
+
+/// Item of a [`BinomialHeap`], moving between tree nodes as keys decrease.
+///
+/// [`BinomialHeap`]: struct.BinomialHeap.html
+#[derive(Debug, Copy, Clone)]
+struct Item<K, T> {
+    key: K,
+    elem: T,
+    /// Tree node holding this item
+    node: Handle,
+}
+
+impl<K, T> Links for Item<K, T> {
+    fn shift(&mut self, offset: usize) {
+        self.node = self.node.shift(offset);
+    }
+}
+
+
+/// Node of a binomial tree in a [`BinomialHeap`].
+///
+/// [`BinomialHeap`]: struct.BinomialHeap.html
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    /// Item held by this node
+    item: Handle,
+    /// Parent (or null for a root)
+    parent: Handle,
+    /// Child of the highest degree (or null)
+    child: Handle,
+    /// Next child of the parent, of the next lower degree (or null)
+    sibling: Handle,
+    /// Number of children
+    degree: usize,
+}
+
+impl Links for Node {
+    fn shift(&mut self, offset: usize) {
+        self.item = self.item.shift(offset);
+        self.parent = self.parent.shift(offset);
+        self.child = self.child.shift(offset);
+        self.sibling = self.sibling.shift(offset);
+    }
+}
+
+
+/// Binomial heap, a forest with at most one binomial tree of each degree.
+///
+/// A tree of degree `d` has `2^d` nodes, so the trees correspond to the ones in the binary
+/// representation of `len`. Melding adds these like binary numbers, linking two trees of the
+/// same degree into one of the next degree as carry. `push`, `pop_min`, `meld` and
+/// `decrease_key` all take `O(log n)` time.
+///
+/// Lowering a key moves the item up its tree, so handles refer to items rather than nodes.
+///
+/// # Example
+/// ```
+/// use vasa::heap::{BinomialHeap, MeldableHeap};
+/// let mut heap = BinomialHeap::new();
+/// heap.push(3, "c");
+/// let a = heap.push(4, "a");
+/// heap.decrease_key(a, 2);
+/// assert_eq!(heap.pop_min(), Some((2, "a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinomialHeap<K, T> {
+    items: Arena<Item<K, T>>,
+    nodes: Arena<Node>,
+    /// roots[d] = root of the tree of degree `d` (or null)
+    roots: Vec<Handle>,
+    len: usize,
+}
+
+impl<K, T> BinomialHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn key(&self, node: Handle) -> K {
+        self.items[self.nodes[node].item].key
+    }
+
+    /// Link two trees of the same degree, the one with the larger root below the other.
+    fn link(&mut self, a: Handle, b: Handle) -> Handle {
+        let (parent, child) = if self.key(b) < self.key(a) { (b, a) } else { (a, b) };
+        self.nodes[child].parent = parent;
+        self.nodes[child].sibling = self.nodes[parent].child;
+        self.nodes[parent].child = child;
+        self.nodes[parent].degree += 1;
+        parent
+    }
+
+    /// Add the tree at `root` to the forest, carrying over to higher degrees.
+    fn add_tree(&mut self, mut root: Handle) {
+        let mut degree = self.nodes[root].degree;
+        loop {
+            if degree >= self.roots.len() {
+                self.roots.resize(degree + 1, Handle::null());
+            }
+            if self.roots[degree].is_null() {
+                self.roots[degree] = root;
+                return;
+            }
+            root = self.link(self.roots[degree], root);
+            self.roots[degree] = Handle::null();
+            degree += 1;
+        }
+    }
+
+    /// Degree of the tree with the smallest root.
+    fn min_degree(&self) -> Option<usize> {
+        (0..self.roots.len())
+            .filter(|&degree| !self.roots[degree].is_null())
+            .min_by_key(|&degree| self.key(self.roots[degree]))
+    }
+}
+
+impl<K, T> Default for BinomialHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn default() -> BinomialHeap<K, T> {
+        BinomialHeap::new()
+    }
+}
+
+impl<K, T> MeldableHeap<K, T> for BinomialHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn new() -> BinomialHeap<K, T> {
+        BinomialHeap { items: Arena::new(), nodes: Arena::new(), roots: Vec::new(), len: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, key: K, elem: T) -> Handle {
+        let item = self.items.insert(Item { key, elem, node: Handle::null() });
+        let node = self.nodes.insert(Node {
+            item,
+            parent: Handle::null(),
+            child: Handle::null(),
+            sibling: Handle::null(),
+            degree: 0,
+        });
+        self.items[item].node = node;
+        self.add_tree(node);
+        self.len += 1;
+        item
+    }
+
+    fn peek(&self) -> Option<(K, T)> {
+        let degree = self.min_degree()?;
+        let item = &self.items[self.nodes[self.roots[degree]].item];
+        Some((item.key, item.elem))
+    }
+
+    fn pop_min(&mut self) -> Option<(K, T)> {
+        let degree = self.min_degree()?;
+        let root = self.roots[degree];
+        self.roots[degree] = Handle::null();
+
+        // The children are binomial trees of all lower degrees
+        let mut child = self.nodes[root].child;
+        while !child.is_null() {
+            let next = self.nodes[child].sibling;
+            self.nodes[child].parent = Handle::null();
+            self.nodes[child].sibling = Handle::null();
+            self.add_tree(child);
+            child = next;
+        }
+        while self.roots.last().is_some_and(|root| root.is_null()) {
+            self.roots.pop();
+        }
+
+        let item = self.nodes[root].item;
+        let Item { key, elem, .. } = self.items[item];
+        self.nodes.remove(root);
+        self.items.remove(item);
+        self.len -= 1;
+        Some((key, elem))
+    }
+
+    fn meld(&mut self, other: BinomialHeap<K, T>) {
+        // Items and nodes are always added and removed together, so there are as many slots of
+        // each, and handles of both are shifted by the same offset
+        let offset = self.items.append(other.items);
+        let node_offset = self.nodes.append(other.nodes);
+        debug_assert_eq!(offset, node_offset);
+        for &root in &other.roots {
+            if !root.is_null() {
+                self.add_tree(root.shift(offset));
+            }
+        }
+        self.len += other.len;
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        assert!(key <= self.items[handle].key, "key of {:?} would increase", handle);
+        self.items[handle].key = key;
+
+        // Swap items with the parent until the heap order holds again
+        let mut node = self.items[handle].node;
+        loop {
+            let parent = self.nodes[node].parent;
+            if parent.is_null() || self.key(parent) <= key {
+                break;
+            }
+            let above = self.nodes[parent].item;
+            self.nodes[parent].item = handle;
+            self.nodes[node].item = above;
+            self.items[handle].node = parent;
+            self.items[above].node = node;
+            node = parent;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_representation() {
+        let mut heap = BinomialHeap::new();
+        for key in 0..13 {
+            heap.push(key, ());
+        }
+        // 13 = 0b1101
+        let degrees: Vec<_> = heap.roots.iter().map(|root| !root.is_null()).collect();
+        assert_eq!(degrees, vec![true, false, true, true]);
+        for &root in heap.roots.iter().filter(|root| !root.is_null()) {
+            assert_eq!(heap.nodes[root].degree, heap.roots.iter().position(|&r| r == root).unwrap());
+        }
+
+        heap.pop_min();
+        // 12 = 0b1100
+        let degrees: Vec<_> = heap.roots.iter().map(|root| !root.is_null()).collect();
+        assert_eq!(degrees, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn handles_follow_items() {
+        let mut heap = BinomialHeap::new();
+        let handles: Vec<_> = (0..8).map(|key| heap.push(10 + key, key)).collect();
+        // The largest key sits at the bottom of the single tree of degree 3
+        heap.decrease_key(handles[7], 0);
+        heap.decrease_key(handles[3], 1);
+        heap.decrease_key(handles[7], 0);
+        assert_eq!(heap.pop_min(), Some((0, 7)));
+        assert_eq!(heap.pop_min(), Some((1, 3)));
+        assert_eq!(heap.pop_min(), Some((10, 0)));
+    }
+}
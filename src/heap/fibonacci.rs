@@ -0,0 +1,313 @@
+use super::{Arena, Handle, Links, MeldableHeap};
+
+
+/// Item of a [`FibonacciHeap`], in a circular list with its siblings.
+///
+/// [`FibonacciHeap`]: struct.FibonacciHeap.html
+#[derive(Debug, Copy, Clone)]
+struct Node<K, T> {
+    key: K,
+    elem: T,
+    /// Parent (or null for a root)
+    parent: Handle,
+    /// Any one of the children (or null)
+    child: Handle,
+    /// Previous sibling, the node itself if it is the only one
+    left: Handle,
+    /// Next sibling, the node itself if it is the only one
+    right: Handle,
+    /// Number of children
+    degree: usize,
+    /// Whether the node lost a child since it became a child itself
+    marked: bool,
+}
+
+impl<K, T> Links for Node<K, T> {
+    fn shift(&mut self, offset: usize) {
+        self.parent = self.parent.shift(offset);
+        self.child = self.child.shift(offset);
+        self.left = self.left.shift(offset);
+        self.right = self.right.shift(offset);
+    }
+}
+
+
+/// Fibonacci heap, a lazily consolidated forest of heap-ordered trees.
+///
+/// `push` and `meld` only add trees to the circular list of roots. `pop_min` links the roots
+/// until no two have the same degree, in `O(log n)` amortized time. `decrease_key` cuts the item
+/// from its parent, along with every ancestor that had lost a child before, taking `O(1)`
+/// amortized time. This makes Dijkstra's algorithm run in `O(m + n log n)`, though the constant
+/// factors are large.
+///
+/// # Example
+/// ```
+/// use vasa::heap::{FibonacciHeap, MeldableHeap};
+/// let mut heap = FibonacciHeap::new();
+/// heap.push(3, "c");
+/// let a = heap.push(4, "a");
+/// heap.decrease_key(a, 2);
+/// assert_eq!(heap.pop_min(), Some((2, "a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FibonacciHeap<K, T> {
+    nodes: Arena<Node<K, T>>,
+    /// Root with the smallest key (or null)
+    min: Handle,
+    len: usize,
+}
+
+impl<K, T> FibonacciHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    /// Join the circular lists containing `a` and `b`.
+    fn splice(&mut self, a: Handle, b: Handle) {
+        let a_right = self.nodes[a].right;
+        let b_left = self.nodes[b].left;
+        self.nodes[a].right = b;
+        self.nodes[b].left = a;
+        self.nodes[b_left].right = a_right;
+        self.nodes[a_right].left = b_left;
+    }
+
+    /// Remove `node` from its circular list, leaving it alone in a list of its own.
+    fn unlink(&mut self, node: Handle) {
+        let Node { left, right, .. } = self.nodes[node];
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+        self.nodes[node].left = node;
+        self.nodes[node].right = node;
+    }
+
+    /// Add the list of roots containing `node` to the root list, updating the minimum.
+    fn add_roots(&mut self, node: Handle) {
+        if self.min.is_null() {
+            self.min = node;
+        } else {
+            self.splice(self.min, node);
+        }
+        if self.nodes[node].key < self.nodes[self.min].key {
+            self.min = node;
+        }
+    }
+
+    /// Make the root `child` a child of the root `parent`.
+    fn link(&mut self, child: Handle, parent: Handle) {
+        self.unlink(child);
+        let first = self.nodes[parent].child;
+        if first.is_null() {
+            self.nodes[parent].child = child;
+        } else {
+            self.splice(first, child);
+        }
+        self.nodes[child].parent = parent;
+        self.nodes[child].marked = false;
+        self.nodes[parent].degree += 1;
+    }
+
+    /// Link roots of the same degree until all degrees differ, then find the new minimum.
+    fn consolidate(&mut self) {
+        let mut roots = vec![self.min];
+        let mut node = self.nodes[self.min].right;
+        while node != self.min {
+            roots.push(node);
+            node = self.nodes[node].right;
+        }
+
+        // by_degree[d] = the root of degree `d` seen so far (or null)
+        let mut by_degree: Vec<Handle> = Vec::new();
+        for mut root in roots {
+            let mut degree = self.nodes[root].degree;
+            loop {
+                if degree >= by_degree.len() {
+                    by_degree.resize(degree + 1, Handle::null());
+                }
+                let other = by_degree[degree];
+                if other.is_null() {
+                    break;
+                }
+                let (parent, child) = if self.nodes[other].key < self.nodes[root].key {
+                    (other, root)
+                } else {
+                    (root, other)
+                };
+                self.link(child, parent);
+                by_degree[degree] = Handle::null();
+                root = parent;
+                degree += 1;
+            }
+            by_degree[degree] = root;
+        }
+
+        self.min = Handle::null();
+        for root in by_degree.into_iter().filter(|root| !root.is_null()) {
+            if self.min.is_null() || self.nodes[root].key < self.nodes[self.min].key {
+                self.min = root;
+            }
+        }
+    }
+
+    /// Move `node` from the children of `parent` to the roots.
+    fn cut(&mut self, node: Handle, parent: Handle) {
+        if self.nodes[node].right == node {
+            self.nodes[parent].child = Handle::null();
+        } else {
+            if self.nodes[parent].child == node {
+                self.nodes[parent].child = self.nodes[node].right;
+            }
+            self.unlink(node);
+        }
+        self.nodes[parent].degree -= 1;
+        self.nodes[node].parent = Handle::null();
+        self.nodes[node].marked = false;
+        self.add_roots(node);
+    }
+}
+
+impl<K, T> Default for FibonacciHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn default() -> FibonacciHeap<K, T> {
+        FibonacciHeap::new()
+    }
+}
+
+impl<K, T> MeldableHeap<K, T> for FibonacciHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn new() -> FibonacciHeap<K, T> {
+        FibonacciHeap { nodes: Arena::new(), min: Handle::null(), len: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, key: K, elem: T) -> Handle {
+        let node = self.nodes.insert(Node {
+            key,
+            elem,
+            parent: Handle::null(),
+            child: Handle::null(),
+            left: Handle::null(),
+            right: Handle::null(),
+            degree: 0,
+            marked: false,
+        });
+        self.nodes[node].left = node;
+        self.nodes[node].right = node;
+        self.add_roots(node);
+        self.len += 1;
+        node
+    }
+
+    fn peek(&self) -> Option<(K, T)> {
+        if self.min.is_null() {
+            None
+        } else {
+            let min = &self.nodes[self.min];
+            Some((min.key, min.elem))
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<(K, T)> {
+        let min = self.peek()?;
+        let node = self.min;
+
+        // All children become roots
+        let child = self.nodes[node].child;
+        if !child.is_null() {
+            let mut c = child;
+            loop {
+                self.nodes[c].parent = Handle::null();
+                c = self.nodes[c].right;
+                if c == child {
+                    break;
+                }
+            }
+            self.splice(node, child);
+        }
+
+        if self.nodes[node].right == node {
+            self.min = Handle::null();
+        } else {
+            self.min = self.nodes[node].right;
+            self.unlink(node);
+            self.consolidate();
+        }
+
+        self.nodes.remove(node);
+        self.len -= 1;
+        Some(min)
+    }
+
+    fn meld(&mut self, other: FibonacciHeap<K, T>) {
+        let offset = self.nodes.append(other.nodes);
+        if !other.min.is_null() {
+            self.add_roots(other.min.shift(offset));
+        }
+        self.len += other.len;
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        assert!(key <= self.nodes[handle].key, "key of {:?} would increase", handle);
+        self.nodes[handle].key = key;
+
+        let parent = self.nodes[handle].parent;
+        if !parent.is_null() && key < self.nodes[parent].key {
+            self.cut(handle, parent);
+
+            // Cascading cut: ancestors losing their second child become roots as well
+            let mut node = parent;
+            loop {
+                let parent = self.nodes[node].parent;
+                if parent.is_null() {
+                    break;
+                }
+                if !self.nodes[node].marked {
+                    self.nodes[node].marked = true;
+                    break;
+                }
+                self.cut(node, parent);
+                node = parent;
+            }
+        } else if key < self.nodes[self.min].key {
+            self.min = handle;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consolidates() {
+        let mut heap = FibonacciHeap::new();
+        let handles: Vec<_> = (0..16).map(|key| heap.push(key, key)).collect();
+        assert_eq!(heap.pop_min(), Some((0, 0)));
+        // 15 items left in trees of distinct degrees
+        let mut degrees = Vec::new();
+        let mut root = heap.min;
+        loop {
+            degrees.push(heap.nodes[root].degree);
+            root = heap.nodes[root].right;
+            if root == heap.min {
+                break;
+            }
+        }
+        degrees.sort();
+        assert_eq!(degrees, vec![0, 1, 2, 3]);
+
+        // Cut leaves of the largest tree
+        heap.decrease_key(handles[15], 0);
+        heap.decrease_key(handles[14], 0);
+        assert_eq!(heap.pop_min().map(|(key, _)| key), Some(0));
+        assert_eq!(heap.pop_min().map(|(key, _)| key), Some(0));
+        assert_eq!(heap.pop_min(), Some((1, 1)));
+    }
+}
@@ -0,0 +1,223 @@
+use super::{Arena, Handle, Links, MeldableHeap};
+
+
+/// Item of a [`LeftistHeap`], a binary tree node.
+///
+/// [`LeftistHeap`]: struct.LeftistHeap.html
+#[derive(Debug, Copy, Clone)]
+struct Node<K, T> {
+    key: K,
+    elem: T,
+    /// Parent (or null for the root)
+    parent: Handle,
+    /// Child with the longer way to a missing child (or null)
+    left: Handle,
+    /// Child with the shorter way to a missing child (or null)
+    right: Handle,
+    /// Number of nodes on the right spine of this subtree
+    rank: usize,
+}
+
+impl<K, T> Links for Node<K, T> {
+    fn shift(&mut self, offset: usize) {
+        self.parent = self.parent.shift(offset);
+        self.left = self.left.shift(offset);
+        self.right = self.right.shift(offset);
+    }
+}
+
+
+/// Leftist heap, a binary tree whose right spine is at most `O(log n)` long.
+///
+/// Two trees are melded by merging their right spines like sorted lists, then swapping
+/// children wherever the left subtree would have the shorter right spine. `push`, `pop_min`
+/// and `meld` all take `O(log n)` time. `decrease_key` cuts the item from its parent, repairs
+/// the spines above and melds the cut subtree back in.
+///
+/// # Example
+/// ```
+/// use vasa::heap::{LeftistHeap, MeldableHeap};
+/// let mut heap = LeftistHeap::new();
+/// heap.push(3, "c");
+/// let a = heap.push(4, "a");
+/// heap.decrease_key(a, 2);
+/// assert_eq!(heap.pop_min(), Some((2, "a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LeftistHeap<K, T> {
+    nodes: Arena<Node<K, T>>,
+    root: Handle,
+    len: usize,
+}
+
+impl<K, T> LeftistHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn rank(&self, node: Handle) -> usize {
+        if node.is_null() { 0 } else { self.nodes[node].rank }
+    }
+
+    /// Swap the children of `node` if needed, returning whether its rank changed.
+    fn repair(&mut self, node: Handle) -> bool {
+        let Node { left, right, rank, .. } = self.nodes[node];
+        if self.rank(left) < self.rank(right) {
+            self.nodes[node].left = right;
+            self.nodes[node].right = left;
+        }
+        let new = self.rank(self.nodes[node].right) + 1;
+        self.nodes[node].rank = new;
+        new != rank
+    }
+
+    /// Merge two trees along their right spines, returning the new root.
+    fn merge(&mut self, a: Handle, b: Handle) -> Handle {
+        if a.is_null() {
+            return b;
+        }
+        if b.is_null() {
+            return a;
+        }
+        let (root, other) = if self.nodes[b].key < self.nodes[a].key { (b, a) } else { (a, b) };
+
+        let right = self.merge(self.nodes[root].right, other);
+        self.nodes[right].parent = root;
+        self.nodes[root].right = right;
+        self.repair(root);
+        root
+    }
+}
+
+impl<K, T> Default for LeftistHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn default() -> LeftistHeap<K, T> {
+        LeftistHeap::new()
+    }
+}
+
+impl<K, T> MeldableHeap<K, T> for LeftistHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn new() -> LeftistHeap<K, T> {
+        LeftistHeap { nodes: Arena::new(), root: Handle::null(), len: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, key: K, elem: T) -> Handle {
+        let node = self.nodes.insert(Node {
+            key,
+            elem,
+            parent: Handle::null(),
+            left: Handle::null(),
+            right: Handle::null(),
+            rank: 1,
+        });
+        self.root = self.merge(self.root, node);
+        self.nodes[self.root].parent = Handle::null();
+        self.len += 1;
+        node
+    }
+
+    fn peek(&self) -> Option<(K, T)> {
+        if self.root.is_null() {
+            None
+        } else {
+            let root = &self.nodes[self.root];
+            Some((root.key, root.elem))
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<(K, T)> {
+        let min = self.peek()?;
+        let Node { left, right, .. } = self.nodes[self.root];
+        for &child in &[left, right] {
+            if !child.is_null() {
+                self.nodes[child].parent = Handle::null();
+            }
+        }
+
+        self.nodes.remove(self.root);
+        self.root = self.merge(left, right);
+        self.len -= 1;
+        Some(min)
+    }
+
+    fn meld(&mut self, other: LeftistHeap<K, T>) {
+        let offset = self.nodes.append(other.nodes);
+        self.root = self.merge(self.root, other.root.shift(offset));
+        if !self.root.is_null() {
+            self.nodes[self.root].parent = Handle::null();
+        }
+        self.len += other.len;
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        assert!(key <= self.nodes[handle].key, "key of {:?} would increase", handle);
+        self.nodes[handle].key = key;
+
+        let parent = self.nodes[handle].parent;
+        if parent.is_null() || self.nodes[parent].key <= key {
+            return;
+        }
+
+        // Cut the subtree at `handle`, shortening spines above it
+        if self.nodes[parent].left == handle {
+            self.nodes[parent].left = Handle::null();
+        } else {
+            self.nodes[parent].right = Handle::null();
+        }
+        self.nodes[handle].parent = Handle::null();
+        let mut node = parent;
+        while !node.is_null() && self.repair(node) {
+            node = self.nodes[node].parent;
+        }
+
+        self.root = self.merge(self.root, handle);
+        self.nodes[self.root].parent = Handle::null();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Check parent links, heap order and ranks of the subtree at `node`, returning its size.
+    fn check(heap: &LeftistHeap<usize, usize>, node: Handle) -> usize {
+        if node.is_null() {
+            return 0;
+        }
+        let Node { key, left, right, rank, .. } = heap.nodes[node];
+        for &child in &[left, right] {
+            if !child.is_null() {
+                assert_eq!(heap.nodes[child].parent, node);
+                assert!(heap.nodes[child].key >= key);
+            }
+        }
+        assert!(heap.rank(left) >= heap.rank(right));
+        assert_eq!(rank, heap.rank(right) + 1);
+        1 + check(heap, left) + check(heap, right)
+    }
+
+    #[test]
+    fn leftist() {
+        let mut heap = LeftistHeap::new();
+        let handles: Vec<_> = (0..100).map(|i| heap.push((i * 37) % 101 + 100, i)).collect();
+        for (i, &handle) in handles.iter().enumerate().step_by(3) {
+            heap.decrease_key(handle, i);
+            assert_eq!(check(&heap, heap.root), heap.len());
+        }
+        for _ in 0..50 {
+            heap.pop_min();
+            assert_eq!(check(&heap, heap.root), heap.len());
+        }
+        // The right spine stays short
+        assert!(heap.rank(heap.root) <= 6);
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+mod binomial;
+mod fibonacci;
+mod leftist;
+mod pairing;
+
+pub use self::binomial::BinomialHeap;
+pub use self::fibonacci::FibonacciHeap;
+pub use self::leftist::LeftistHeap;
+pub use self::pairing::PairingHeap;
+
+
+/// Index of an item in a heap, returned by `push` and used to `decrease_key` it later.
+///
+/// Works like [`linked_list::Pointer`]: items live in a `Vec`, and handles are indices into it.
+/// A handle stays valid until its item is popped, after which its slot may be reused.
+///
+/// [`linked_list::Pointer`]: ../linked_list/struct.Pointer.html
+#[derive(PartialEq, Copy, Clone, Eq)]
+pub struct Handle(usize);
+
+impl Handle {
+    /// `!0` is the largest possible `usize` value. We have other problems if we were to get that
+    /// many items.
+    #[inline]
+    pub fn null() -> Handle {
+        Handle(!0)
+    }
+
+    /// Returns `true` if this handle is null.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        *self == Handle::null()
+    }
+
+    /// Handle moved by `offset`, as all handles of a heap are when it is melded into another.
+    #[inline]
+    fn shift(self, offset: usize) -> Handle {
+        if self.is_null() {
+            self
+        } else {
+            Handle(self.0 + offset)
+        }
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_null() {
+            write!(f, "h-")
+        } else {
+            write!(f, "h{}", self.0)
+        }
+    }
+}
+
+
+/// Min-heap of items with keys, which can be melded with another heap of the same kind, and
+/// whose items' keys can be lowered through the [`Handle`] returned by `push`.
+///
+/// Ties between equal keys are broken arbitrarily.
+///
+/// # Example
+/// ```
+/// use vasa::heap::{MeldableHeap, PairingHeap};
+/// let mut heap = PairingHeap::new();
+/// heap.push(5, 'a');
+/// let b = heap.push(7, 'b');
+/// let mut other = PairingHeap::new();
+/// other.push(6, 'c');
+/// heap.meld(other);
+/// heap.decrease_key(b, 1);
+/// assert_eq!(heap.len(), 3);
+/// assert_eq!(heap.pop_min(), Some((1, 'b')));
+/// assert_eq!(heap.pop_min(), Some((5, 'a')));
+/// assert_eq!(heap.pop_min(), Some((6, 'c')));
+/// assert_eq!(heap.pop_min(), None);
+/// ```
+///
+/// [`Handle`]: struct.Handle.html
+pub trait MeldableHeap<K, T>: Sized
+    where K: Ord + Copy,
+          T: Copy
+{
+    /// Creating a new and empty heap.
+    fn new() -> Self;
+
+    /// Return number of items in the heap.
+    fn len(&self) -> usize;
+
+    /// Test if the heap is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adding `elem` with the given `key`, returning a handle to it.
+    fn push(&mut self, key: K, elem: T) -> Handle;
+
+    /// Item with the smallest key, and its key.
+    fn peek(&self) -> Option<(K, T)>;
+
+    /// Removing the item with the smallest key, returning it and its key.
+    fn pop_min(&mut self) -> Option<(K, T)>;
+
+    /// Moving all items of `other` into this heap.
+    ///
+    /// Handles into this heap stay valid, handles into `other` do not.
+    fn meld(&mut self, other: Self);
+
+    /// Lowering the key of the item at `handle` to `key`.
+    ///
+    /// # Panics
+    /// If `key` is larger than the current key of the item.
+    fn decrease_key(&mut self, handle: Handle, key: K);
+}
+
+
+/// Nodes of a heap, linking each other with [`Handle`]s.
+trait Links {
+    /// Moving all handles by `offset`.
+    fn shift(&mut self, offset: usize);
+}
+
+
+/// Storage for the nodes of a heap, reusing the slots of removed nodes.
+#[derive(Debug, Clone)]
+struct Arena<N> {
+    items: Vec<N>,
+    freed: Vec<Handle>,
+}
+
+impl<N> Index<Handle> for Arena<N> {
+    type Output = N;
+
+    fn index(&self, index: Handle) -> &N {
+        &self.items[index.0]
+    }
+}
+
+impl<N> IndexMut<Handle> for Arena<N> {
+    fn index_mut(&mut self, index: Handle) -> &mut N {
+        &mut self.items[index.0]
+    }
+}
+
+impl<N: Links> Arena<N> {
+    fn new() -> Arena<N> {
+        Arena { items: Vec::new(), freed: Vec::new() }
+    }
+
+    /// Insert `node`, overwriting a removed one first, if available.
+    fn insert(&mut self, node: N) -> Handle {
+        if let Some(handle) = self.freed.pop() {
+            self[handle] = node;
+            handle
+        } else {
+            self.items.push(node);
+            Handle(self.items.len() - 1)
+        }
+    }
+
+    /// Mark the slot at `handle` as reusable. The node must not be linked anymore.
+    fn remove(&mut self, handle: Handle) {
+        self.freed.push(handle);
+    }
+
+    /// Move all nodes of `other` behind the own ones, returning the offset of their handles.
+    fn append(&mut self, other: Arena<N>) -> usize {
+        let offset = self.items.len();
+        self.items.extend(other.items.into_iter().map(|mut node| {
+            node.shift(offset);
+            node
+        }));
+        self.freed.extend(other.freed.into_iter().map(|handle| handle.shift(offset)));
+        offset
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::XorShift;
+
+    // Random pushes, decreases and pops, checked against a sorted list.
+    fn like_sorting<H: MeldableHeap<usize, usize>>() {
+        let mut rng = XorShift::new(77);
+        for _ in 0..200 {
+            let mut heap = H::new();
+            // items[elem] = (handle, key), or `None` once popped
+            let mut items: Vec<Option<(Handle, usize)>> = Vec::new();
+            for _ in 0..100 {
+                match rng.below(4) {
+                    0 | 1 => {
+                        let key = rng.below(1000);
+                        items.push(Some((heap.push(key, items.len()), key)));
+                    }
+                    2 => {
+                        let elem = rng.below(items.len() + 1);
+                        if let Some(Some((handle, key))) = items.get(elem).copied() {
+                            let lower = rng.below(key + 1);
+                            heap.decrease_key(handle, lower);
+                            items[elem] = Some((handle, lower));
+                        }
+                    }
+                    _ => {
+                        let min = items.iter().flatten().map(|&(_, key)| key).min();
+                        assert_eq!(heap.peek().map(|(key, _)| key), min);
+                        if let Some((key, elem)) = heap.pop_min() {
+                            assert_eq!(items[elem].map(|(_, key)| key), Some(key));
+                            items[elem] = None;
+                        }
+                    }
+                }
+                assert_eq!(heap.len(), items.iter().flatten().count());
+            }
+
+            let mut expected: Vec<_> = items.iter().flatten().map(|&(_, key)| key).collect();
+            expected.sort();
+            let mut popped = Vec::new();
+            while let Some((key, _)) = heap.pop_min() {
+                popped.push(key);
+            }
+            assert_eq!(popped, expected);
+            assert!(heap.is_empty());
+        }
+    }
+
+    // Melding heaps with popped items, and decreasing keys through the surviving handles.
+    fn melds<H: MeldableHeap<usize, usize>>() {
+        let mut rng = XorShift::new(78);
+        for _ in 0..200 {
+            let mut heaps: Vec<(H, Vec<(Handle, usize)>)> = Vec::new();
+            for _ in 0..2 {
+                let mut heap = H::new();
+                let mut handles = Vec::new();
+                for elem in 0..rng.below(30) {
+                    handles.push((heap.push(100 + rng.below(1000), elem), elem));
+                }
+                for _ in 0..rng.below(5) {
+                    if let Some((_, elem)) = heap.pop_min() {
+                        handles.retain(|&(_, e)| e != elem);
+                    }
+                }
+                heaps.push((heap, handles));
+            }
+            let (other, _) = heaps.pop().unwrap();
+            let (mut heap, handles) = heaps.pop().unwrap();
+            let len = heap.len() + other.len();
+            heap.meld(other);
+            assert_eq!(heap.len(), len);
+
+            // Handles into `heap` survive the meld
+            for (i, &(handle, _)) in handles.iter().enumerate() {
+                heap.decrease_key(handle, i);
+                assert_eq!(heap.peek(), Some((0, handles[0].1)));
+            }
+
+            let mut popped = Vec::new();
+            while let Some((key, _)) = heap.pop_min() {
+                popped.push(key);
+            }
+            assert_eq!(popped.len(), len);
+            assert!(popped.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    fn handle() {
+        assert!(Handle::null().is_null());
+        assert_eq!(Handle::null().shift(3), Handle::null());
+        assert_eq!(Handle(2).shift(3), Handle(5));
+        assert_eq!(format!("{:?} {:?}", Handle(4), Handle::null()), "h4 h-");
+    }
+
+    #[test]
+    fn fibonacci() {
+        like_sorting::<FibonacciHeap<_, _>>();
+        melds::<FibonacciHeap<_, _>>();
+    }
+
+    #[test]
+    fn pairing() {
+        like_sorting::<PairingHeap<_, _>>();
+        melds::<PairingHeap<_, _>>();
+    }
+
+    #[test]
+    fn binomial() {
+        like_sorting::<BinomialHeap<_, _>>();
+        melds::<BinomialHeap<_, _>>();
+    }
+
+    #[test]
+    fn leftist() {
+        like_sorting::<LeftistHeap<_, _>>();
+        melds::<LeftistHeap<_, _>>();
+    }
+}
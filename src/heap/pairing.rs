@@ -0,0 +1,208 @@
+use super::{Arena, Handle, Links, MeldableHeap};
+
+
+/// Item of a [`PairingHeap`], in the list of children of its parent.
+///
+/// [`PairingHeap`]: struct.PairingHeap.html
+#[derive(Debug, Copy, Clone)]
+struct Node<K, T> {
+    key: K,
+    elem: T,
+    /// First child (or null)
+    child: Handle,
+    /// Next sibling (or null)
+    next: Handle,
+    /// Previous sibling, the parent for the first child (or null for the root)
+    prev: Handle,
+}
+
+impl<K, T> Links for Node<K, T> {
+    fn shift(&mut self, offset: usize) {
+        self.child = self.child.shift(offset);
+        self.next = self.next.shift(offset);
+        self.prev = self.prev.shift(offset);
+    }
+}
+
+
+/// Pairing heap, a single tree where linking two trees makes the larger root a child of the
+/// smaller one.
+///
+/// `push`, `meld` and `decrease_key` just link a tree to the root in `O(1)`. `pop_min` pairs up
+/// the children of the root from left to right, then links the pairs from right to left, taking
+/// `O(log n)` amortized time. Simple and fast in practice.
+///
+/// # Example
+/// ```
+/// use vasa::heap::{MeldableHeap, PairingHeap};
+/// let mut heap = PairingHeap::new();
+/// heap.push(3, "c");
+/// let a = heap.push(4, "a");
+/// heap.decrease_key(a, 2);
+/// assert_eq!(heap.peek(), Some((2, "a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PairingHeap<K, T> {
+    nodes: Arena<Node<K, T>>,
+    root: Handle,
+    len: usize,
+}
+
+impl<K, T> PairingHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    /// Make the root with the larger key the first child of the other, returning the new root.
+    fn link(&mut self, a: Handle, b: Handle) -> Handle {
+        if a.is_null() {
+            return b;
+        }
+        if b.is_null() {
+            return a;
+        }
+        let (parent, child) = if self.nodes[b].key < self.nodes[a].key { (b, a) } else { (a, b) };
+
+        let first = self.nodes[parent].child;
+        if !first.is_null() {
+            self.nodes[first].prev = child;
+        }
+        self.nodes[child].next = first;
+        self.nodes[child].prev = parent;
+        self.nodes[parent].child = child;
+        parent
+    }
+
+    /// Cut the tree at `node` from its parent, making it a root.
+    fn cut(&mut self, node: Handle) {
+        let Node { prev, next, .. } = self.nodes[node];
+        if self.nodes[prev].child == node {
+            self.nodes[prev].child = next;
+        } else {
+            self.nodes[prev].next = next;
+        }
+        if !next.is_null() {
+            self.nodes[next].prev = prev;
+        }
+        self.nodes[node].prev = Handle::null();
+        self.nodes[node].next = Handle::null();
+    }
+}
+
+impl<K, T> Default for PairingHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn default() -> PairingHeap<K, T> {
+        PairingHeap::new()
+    }
+}
+
+impl<K, T> MeldableHeap<K, T> for PairingHeap<K, T>
+    where K: Ord + Copy,
+          T: Copy
+{
+    fn new() -> PairingHeap<K, T> {
+        PairingHeap { nodes: Arena::new(), root: Handle::null(), len: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, key: K, elem: T) -> Handle {
+        let node = self.nodes.insert(Node {
+            key,
+            elem,
+            child: Handle::null(),
+            next: Handle::null(),
+            prev: Handle::null(),
+        });
+        self.root = self.link(self.root, node);
+        self.len += 1;
+        node
+    }
+
+    fn peek(&self) -> Option<(K, T)> {
+        if self.root.is_null() {
+            None
+        } else {
+            let root = &self.nodes[self.root];
+            Some((root.key, root.elem))
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<(K, T)> {
+        let min = self.peek()?;
+        let root = self.root;
+
+        // First pass: link the children in pairs, from left to right
+        let mut pairs = Vec::new();
+        let mut child = self.nodes[root].child;
+        while !child.is_null() {
+            let first = child;
+            let second = self.nodes[first].next;
+            child = if second.is_null() { second } else { self.nodes[second].next };
+            for &node in &[first, second] {
+                if !node.is_null() {
+                    self.nodes[node].prev = Handle::null();
+                    self.nodes[node].next = Handle::null();
+                }
+            }
+            pairs.push(self.link(first, second));
+        }
+
+        // Second pass: link the pairs from right to left
+        self.root = Handle::null();
+        while let Some(pair) = pairs.pop() {
+            self.root = self.link(pair, self.root);
+        }
+
+        self.nodes.remove(root);
+        self.len -= 1;
+        Some(min)
+    }
+
+    fn meld(&mut self, other: PairingHeap<K, T>) {
+        let offset = self.nodes.append(other.nodes);
+        self.root = self.link(self.root, other.root.shift(offset));
+        self.len += other.len;
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        assert!(key <= self.nodes[handle].key, "key of {:?} would increase", handle);
+        self.nodes[handle].key = key;
+        if handle != self.root {
+            self.cut(handle);
+            self.root = self.link(self.root, handle);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrease_child() {
+        let mut heap = PairingHeap::new();
+        heap.push(1, 0);
+        let handles: Vec<_> = (2..6).map(|key| heap.push(key, key)).collect();
+        // All other items are children of the root, cut one from the middle
+        heap.decrease_key(handles[1], 0);
+        assert_eq!(heap.pop_min(), Some((0, 3)));
+        assert_eq!(heap.pop_min(), Some((1, 0)));
+        assert_eq!(heap.pop_min(), Some((2, 2)));
+        assert_eq!(heap.pop_min(), Some((4, 4)));
+        assert_eq!(heap.pop_min(), Some((5, 5)));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn increase_key() {
+        let mut heap = PairingHeap::new();
+        let handle = heap.push(1, ());
+        heap.decrease_key(handle, 2);
+    }
+}
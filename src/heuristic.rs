@@ -0,0 +1,192 @@
+use crate::weight::{OrderedFloat, Weight};
+
+
+/// Estimate of the remaining cost from a node to the goal of a search.
+///
+/// For A* to find shortest paths the estimate must never exceed the actual cost (admissible),
+/// and must not drop by more than the cost of any edge (consistent): `h(u) <= cost + h(v)` for
+/// every edge `u -> v`, and `h(goal) == 0`.
+///
+/// Any closure `Fn(usize) -> W` is a heuristic.
+///
+/// # Example
+/// ```
+/// use vasa::heuristic::Heuristic;
+/// let h = |node: usize| 10 - node;
+/// assert_eq!(h.estimate(4), 6);
+/// ```
+pub trait Heuristic<W> {
+    /// Lower bound of the cost from `node` to the goal.
+    fn estimate(&self, node: usize) -> W;
+}
+
+impl<W, F> Heuristic<W> for F
+    where F: Fn(usize) -> W
+{
+    fn estimate(&self, node: usize) -> W {
+        self(node)
+    }
+}
+
+
+/// Straight-line distance to the goal, for graphs with node coordinates.
+///
+/// Admissible if every edge costs at least `scale` times the distance between its nodes.
+///
+/// # Example
+/// ```
+/// use vasa::heuristic::{Euclidean, Heuristic};
+/// let coords = [(0.0, 0.0), (3.0, 4.0)];
+/// let h = Euclidean::new(&coords, 1);
+/// assert_eq!(Heuristic::<u32>::estimate(&h, 0), 5);
+/// assert_eq!(Heuristic::<u32>::estimate(&h, 1), 0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Euclidean<'a> {
+    coords: &'a [(f64, f64)],
+    goal: (f64, f64),
+    scale: f64,
+}
+
+impl<'a> Euclidean<'a> {
+    /// Creating a new `Euclidean` heuristic towards `goal`, with `coords[node]` being the
+    /// position of each node.
+    pub fn new(coords: &'a [(f64, f64)], goal: usize) -> Euclidean<'a> {
+        Euclidean { coords, goal: coords[goal], scale: 1.0 }
+    }
+
+    /// Scaling all estimates, e.g. by the inverse of the maximum speed when costs are travel
+    /// times.
+    pub fn scale(self, scale: f64) -> Euclidean<'a> {
+        Euclidean { scale, ..self }
+    }
+
+    fn distance(&self, node: usize) -> f64 {
+        let (x, y) = self.coords[node];
+        let (gx, gy) = self.goal;
+        self.scale * (x - gx).hypot(y - gy)
+    }
+}
+
+
+/// Sum of the coordinate differences to the goal, for graphs with node coordinates.
+///
+/// Admissible if every edge costs at least `scale` times the Manhattan distance between its
+/// nodes, as on grids without diagonal moves.
+///
+/// # Example
+/// ```
+/// use vasa::heuristic::{Heuristic, Manhattan};
+/// let coords = [(0.0, 0.0), (3.0, 4.0)];
+/// let h = Manhattan::new(&coords, 1);
+/// assert_eq!(Heuristic::<u32>::estimate(&h, 0), 7);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Manhattan<'a> {
+    coords: &'a [(f64, f64)],
+    goal: (f64, f64),
+    scale: f64,
+}
+
+impl<'a> Manhattan<'a> {
+    /// Creating a new `Manhattan` heuristic towards `goal`, with `coords[node]` being the
+    /// position of each node.
+    pub fn new(coords: &'a [(f64, f64)], goal: usize) -> Manhattan<'a> {
+        Manhattan { coords, goal: coords[goal], scale: 1.0 }
+    }
+
+    /// Scaling all estimates, e.g. by the inverse of the maximum speed when costs are travel
+    /// times.
+    pub fn scale(self, scale: f64) -> Manhattan<'a> {
+        Manhattan { scale, ..self }
+    }
+
+    fn distance(&self, node: usize) -> f64 {
+        let (x, y) = self.coords[node];
+        let (gx, gy) = self.goal;
+        self.scale * ((x - gx).abs() + (y - gy).abs())
+    }
+}
+
+
+// Floating point estimates are shrunk by a tiny margin, so rounding errors can not turn an
+// exactly tight estimate into an overestimate.
+pub(crate) const ROUNDING_MARGIN: f64 = 1.0 - 1e-9;
+
+macro_rules! impl_geometric_heuristic {
+    ($($h:ident),*) => {
+        $(
+            impl Heuristic<OrderedFloat<f64>> for $h<'_> {
+                fn estimate(&self, node: usize) -> OrderedFloat<f64> {
+                    OrderedFloat(self.distance(node) * ROUNDING_MARGIN)
+                }
+            }
+
+            impl_geometric_heuristic!(@int $h; u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+        )*
+    };
+    (@int $h:ident; $($t:ty),*) => {
+        $(
+            // Integer costs are at least as large as the rounded down distance.
+            impl Heuristic<$t> for $h<'_> {
+                fn estimate(&self, node: usize) -> $t {
+                    self.distance(node).floor() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_geometric_heuristic!(Euclidean, Manhattan);
+
+
+/// Check `h(u) <= cost + h(v)` for an edge `u -> v`, flagging inconsistent heuristics in debug
+/// builds.
+#[inline]
+pub(crate) fn debug_check_consistent<W, H>(heuristic: &H, u: usize, v: usize, cost: W)
+    where W: Weight,
+          H: Heuristic<W>
+{
+    if cfg!(debug_assertions) {
+        let (hu, hv) = (heuristic.estimate(u), heuristic.estimate(v));
+        assert!(hu <= cost.saturating_add(hv),
+                "inconsistent heuristic: h({}) = {:?} > {:?} + h({}) = {:?} + {:?}",
+                u, hu, cost, v, cost, hv);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closure() {
+        let h = |node: usize| node as u64 * 2;
+        assert_eq!(h.estimate(3), 6);
+    }
+
+    #[test]
+    fn euclidean_float() {
+        let coords = [(1.0, 1.0), (4.0, 5.0)];
+        let h = Euclidean::new(&coords, 0);
+        let OrderedFloat(d) = h.estimate(1);
+        assert!(d <= 5.0 && d > 4.999);
+        assert_eq!(Heuristic::<OrderedFloat<f64>>::estimate(&h, 0), OrderedFloat(0.0));
+    }
+
+    #[test]
+    fn manhattan_scaled() {
+        let coords = [(0.0, 0.0), (-2.0, 3.0)];
+        let h = Manhattan::new(&coords, 0).scale(2.0);
+        assert_eq!(Heuristic::<i32>::estimate(&h, 1), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn inconsistent() {
+        let h = |node: usize| if node == 0 { 10 } else { 0 };
+        debug_check_consistent(&h, 0, 1, 3u32);
+    }
+}
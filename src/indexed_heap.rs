@@ -0,0 +1,224 @@
+/// Position of nodes not in the heap. We have other problems if we were to get that many nodes.
+const ABSENT: usize = !0;
+
+
+/// Binary min-heap of nodes `0..n`, each contained at most once, supporting `decrease_key`.
+///
+/// Besides the heap itself, the position of every node in the heap is kept, so the key of a
+/// node can be lowered in place instead of pushing it again. The heap therefore never holds more
+/// than `n` entries, unlike the lazy-deletion `BinaryHeap` of [`dijkstra`], which may hold one
+/// entry per relaxed edge.
+///
+/// # Example
+/// ```
+/// use vasa::indexed_heap::IndexedHeap;
+/// let mut heap = IndexedHeap::new(4);
+/// heap.push(2, 10);
+/// heap.push(3, 7);
+/// heap.decrease_key(2, 5);
+/// assert_eq!(heap.len(), 2);
+/// assert_eq!(heap.pop(), Some((2, 5)));
+/// assert_eq!(heap.pop(), Some((3, 7)));
+/// assert_eq!(heap.pop(), None);
+/// ```
+///
+/// [`dijkstra`]: ../dijkstra/fn.dijkstra.html
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K> {
+    /// entries in heap order, as `(key, node)`
+    heap: Vec<(K, usize)>,
+    /// position[node] = index of `node` in `heap`, or `ABSENT`
+    position: Vec<usize>,
+}
+
+impl<K: Ord + Copy> IndexedHeap<K> {
+    /// Creating a new and empty `IndexedHeap` for the nodes `0..n`.
+    pub fn new(n: usize) -> IndexedHeap<K> {
+        IndexedHeap {
+            heap: Vec::new(),
+            position: vec![ABSENT; n],
+        }
+    }
+
+    /// Return number of nodes in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Test if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Test if `node` is in the heap.
+    pub fn contains(&self, node: usize) -> bool {
+        self.position[node] != ABSENT
+    }
+
+    /// Key of `node`, if it is in the heap.
+    pub fn key(&self, node: usize) -> Option<K> {
+        if self.contains(node) {
+            Some(self.heap[self.position[node]].0)
+        } else {
+            None
+        }
+    }
+
+    /// Node with the smallest key, and its key.
+    pub fn peek(&self) -> Option<(usize, K)> {
+        self.heap.first().map(|&(key, node)| (node, key))
+    }
+
+    /// Adding `node` with the given `key`.
+    ///
+    /// # Panics
+    /// If `node` is in the heap already.
+    pub fn push(&mut self, node: usize, key: K) {
+        assert!(!self.contains(node), "node {} is in the heap already", node);
+        self.heap.push((key, node));
+        self.position[node] = self.heap.len() - 1;
+        self.sift_up(self.heap.len() - 1);
+    }
+
+    /// Lowering the key of `node` to `key`.
+    ///
+    /// # Panics
+    /// If `node` is not in the heap, or `key` is larger than its current key.
+    pub fn decrease_key(&mut self, node: usize, key: K) {
+        assert!(self.contains(node), "node {} is not in the heap", node);
+        let i = self.position[node];
+        assert!(key <= self.heap[i].0, "key of node {} would increase", node);
+        self.heap[i].0 = key;
+        self.sift_up(i);
+    }
+
+    /// Adding `node` with `key`, or lowering its key if that is smaller than the current one.
+    ///
+    /// Returns `false` if the node is in the heap already with a key that is at most `key`.
+    pub fn push_or_decrease(&mut self, node: usize, key: K) -> bool {
+        match self.key(node) {
+            None => self.push(node, key),
+            Some(old) if key < old => self.decrease_key(node, key),
+            Some(_) => return false,
+        }
+        true
+    }
+
+    /// Removing the node with the smallest key, returning it and its key.
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (key, node) = self.heap.pop().unwrap();
+        self.position[node] = ABSENT;
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((node, key))
+    }
+
+    /// Swap two entries, keeping track of their positions.
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.position[self.heap[i].1] = i;
+        self.position[self.heap[j].1] = j;
+    }
+
+    /// Move the entry at `i` up until its parent is smaller.
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[parent] <= self.heap[i] {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    /// Move the entry at `i` down until its children are larger.
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut smallest = i;
+            for child in (2 * i + 1)..(2 * i + 3).min(self.heap.len()) {
+                if self.heap[child] < self.heap[smallest] {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                break;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn empty() {
+        let mut heap = IndexedHeap::<u32>::new(3);
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn push_or_decrease() {
+        let mut heap = IndexedHeap::new(2);
+        assert!(heap.push_or_decrease(0, 5));
+        assert!(!heap.push_or_decrease(0, 6));
+        assert!(heap.push_or_decrease(0, 4));
+        assert_eq!(heap.key(0), Some(4));
+        assert_eq!(heap.key(1), None);
+        assert_eq!(heap.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_twice() {
+        let mut heap = IndexedHeap::new(2);
+        heap.push(1, 5);
+        heap.push(1, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn increase_key() {
+        let mut heap = IndexedHeap::new(2);
+        heap.push(1, 5);
+        heap.decrease_key(1, 6);
+    }
+
+    #[test]
+    fn sorts() {
+        let mut rng = XorShift::new(5);
+        for _ in 0..200 {
+            let n = 1 + rng.below(50);
+            let mut heap = IndexedHeap::new(n);
+            let mut keys = vec![None; n];
+            for _ in 0..3 * n {
+                let node = rng.below(n);
+                let key = rng.below(1000);
+                heap.push_or_decrease(node, key);
+                keys[node] = Some(keys[node].map_or(key, |old: usize| old.min(key)));
+            }
+            assert_eq!(heap.len(), keys.iter().flatten().count());
+
+            let mut expected: Vec<_> = (0..n).filter_map(|node| keys[node].map(|key| (key, node))).collect();
+            expected.sort();
+            let mut popped = Vec::new();
+            while let Some((node, key)) = heap.pop() {
+                popped.push((key, node));
+            }
+            assert_eq!(popped, expected);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Graphs as edge lists in CSV, one `from,to,cost` line per edge.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::graph::{AdjacencyList, Edge, Graph};
+use super::ParseError;
+
+
+/// Parsing an edge list of `from,to,cost` lines.
+///
+/// Nodes are numbered from 0, and the graph gets as many nodes as needed for the largest one
+/// mentioned. A first line without any number is taken as header and skipped, as are empty
+/// lines. Fields may be surrounded by spaces.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra;
+/// use vasa::io::csv::parse_csv;
+/// let graph = parse_csv::<u32>("from,to,cost\n0,1,4\n1,2,3\n0,2,9\n").unwrap();
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(dijkstra(&graph, 0, 2), Some(7));
+///
+/// assert_eq!(parse_csv::<u32>("0,1,4\n1,2\n").unwrap_err().to_string(),
+///            "line 2: expected 3 fields, found 2");
+/// ```
+pub fn parse_csv<W: FromStr>(text: &str) -> Result<AdjacencyList<W>, ParseError> {
+    let mut edges = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let fields: Vec<_> = line.split(',').map(str::trim).collect();
+        let header = number == 1 && fields.iter().all(|field| field.parse::<f64>().is_err());
+        if line.trim().is_empty() || header {
+            continue;
+        }
+        if fields.len() != 3 {
+            return Err(ParseError::new(number, format!("expected 3 fields, found {}", fields.len())));
+        }
+        let node = |field: &str| field.parse::<usize>()
+            .map_err(|_| ParseError::new(number, format!("invalid node `{}`", field)));
+        let cost = fields[2].parse()
+            .map_err(|_| ParseError::new(number, format!("invalid cost `{}`", fields[2])))?;
+        edges.push((node(fields[0])?, node(fields[1])?, cost));
+    }
+    Ok(AdjacencyList::from_edges(edges))
+}
+
+
+/// Writing a graph as edge list, with a `from,to,cost` header.
+///
+/// Nodes without edges after the last one mentioned are lost, as the format has no node count.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::io::csv::write_csv;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (1, 2, 7)]);
+/// let mut out = Vec::new();
+/// write_csv(&graph, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "from,to,cost\n0,1,5\n1,2,7\n");
+/// ```
+pub fn write_csv<W, G, O>(graph: &G, mut out: O) -> io::Result<()>
+    where W: Display,
+          G: Graph<Weight = W>,
+          O: Write
+{
+    writeln!(out, "from,to,cost")?;
+    for from in 0..graph.node_count() {
+        for Edge { node, cost } in graph.neighbors(from) {
+            writeln!(out, "{},{},{}", from, node, cost)?;
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn round_trip() {
+        let mut rng = XorShift::new(190);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let mut out = Vec::new();
+            write_csv(&graph, &mut out).unwrap();
+            let parsed = parse_csv(&String::from_utf8(out).unwrap()).unwrap();
+            // Trailing nodes without edges are lost
+            assert!(parsed.node_count() <= graph.node_count());
+            for node in 0..graph.node_count() {
+                let edges = if node < parsed.node_count() { parsed.neighbors(node) } else { &[] };
+                assert_eq!(edges, graph.neighbors(node));
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text| parse_csv::<u32>(text).unwrap_err();
+        assert_eq!(error("0,1,1\n\n 1 , x , 2\n"), ParseError::new(3, "invalid node `x`"));
+        assert_eq!(error("a,b,c\n0,1,-1\n"), ParseError::new(2, "invalid cost `-1`"));
+        assert_eq!(error("0,1,2,3\n"), ParseError::new(1, "expected 3 fields, found 4"));
+        // Only lines without any number are headers
+        assert_eq!(error("a,1,2\n0,1,1\n"), ParseError::new(1, "invalid node `a`"));
+        assert_eq!(error("0a,1,2\n"), ParseError::new(1, "invalid node `0a`"));
+    }
+
+    #[test]
+    fn header() {
+        let graph = parse_csv::<u32>("source, target, weight\n0,1,1\n").unwrap();
+        assert_eq!(graph.neighbors(0), &[Edge { node: 1, cost: 1 }]);
+        assert_eq!(parse_csv::<u32>("\n0,1,1\n").unwrap().node_count(), 2);
+    }
+}
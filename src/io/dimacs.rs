@@ -0,0 +1,341 @@
+//! Graphs and queries in the formats of the [9th DIMACS Implementation Challenge] on shortest
+//! paths.
+//!
+//! All files consist of lines starting with a letter telling their kind. Lines starting with `c`
+//! are comments, and a single `p` line states the size. Nodes are numbered from 1 in the files,
+//! and from 0 in this crate.
+//!
+//! ```text
+//! c graph (.gr): `p sp <nodes> <arcs>`, then one `a <from> <to> <cost>` per arc
+//! p sp 3 2
+//! a 1 2 5
+//! a 2 3 7
+//! c coordinates (.co): `p aux sp co <nodes>`, then one `v <node> <x> <y>` per node
+//! c single-source queries (.ss): `p aux sp ss <count>`, then one `s <source>` per query
+//! c point-to-point queries (.p2p): `p aux sp p2p <count>`, then one `q <start> <goal>` per query
+//! ```
+//!
+//! [9th DIMACS Implementation Challenge]: http://www.diag.uniroma1.it/challenge9/
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::graph::{AdjacencyList, Edge, Graph};
+use super::ParseError;
+
+
+/// Non-comment lines of `text` with their number, split into words.
+fn records(text: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split_whitespace().collect::<Vec<_>>()))
+        .filter(|(_, words)| !words.is_empty() && words[0] != "c")
+}
+
+
+/// Parsing word `i` of a line, described as `what` in errors.
+fn field<T: FromStr>(line: usize, words: &[&str], i: usize, what: &str) -> Result<T, ParseError> {
+    let word = words.get(i)
+        .ok_or_else(|| ParseError::new(line, format!("missing {}", what)))?;
+    word.parse().map_err(|_| ParseError::new(line, format!("invalid {} `{}`", what, word)))
+}
+
+
+/// Parsing a node number between 1 and `n`, returning it counted from 0.
+fn node(line: usize, words: &[&str], i: usize, n: usize) -> Result<usize, ParseError> {
+    let node: usize = field(line, words, i, "node")?;
+    if node == 0 || node > n {
+        return Err(ParseError::new(line, format!("node {} not in 1..={}", node, n)));
+    }
+    Ok(node - 1)
+}
+
+
+/// Records following a problem line `p <format...> <counts...>`, all of the same `kind`.
+///
+/// The `p` line has to come first, and the number of records has to match its last count.
+struct Problem<'a, I> {
+    records: I,
+    kind: &'a str,
+    expected: usize,
+    found: usize,
+    last_line: usize,
+}
+
+impl<'a, 'b, I> Problem<'a, I>
+    where I: Iterator<Item = (usize, Vec<&'b str>)>
+{
+    /// Parsing the problem line of `format` with `n` counts, returning them too.
+    fn parse(mut records: I, format: &[&str], n: usize, kind: &'a str)
+        -> Result<(Problem<'a, I>, Vec<usize>), ParseError> {
+
+        let expected = format!("p {}", format.join(" "));
+        let (line, words) = records.next()
+            .ok_or_else(|| ParseError::new(1, format!("missing `{}` line", expected)))?;
+        if words[0] != "p" || words.len() <= format.len() || words[1..=format.len()] != *format {
+            return Err(ParseError::new(line, format!("expected `{}` line", expected)));
+        }
+        let counts = (0..n)
+            .map(|i| field(line, &words, 1 + format.len() + i, "count"))
+            .collect::<Result<Vec<usize>, _>>()?;
+        let problem = Problem { records, kind, expected: counts[n - 1], found: 0, last_line: line };
+        Ok((problem, counts))
+    }
+
+    /// Next record, which has to be of `kind`, and `None` after checking the count at the end.
+    fn next(&mut self) -> Result<Option<(usize, Vec<&'b str>)>, ParseError> {
+        match self.records.next() {
+            Some((line, words)) => {
+                if words[0] != self.kind {
+                    return Err(ParseError::new(line, format!("unexpected `{}` line", words[0])));
+                }
+                self.found += 1;
+                self.last_line = line;
+                if self.found > self.expected {
+                    return Err(ParseError::new(line, format!("more than {} `{}` lines", self.expected, self.kind)));
+                }
+                Ok(Some((line, words)))
+            }
+            None if self.found < self.expected => {
+                Err(ParseError::new(self.last_line,
+                                    format!("expected {} `{}` lines, found {}", self.expected, self.kind, self.found)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+
+/// Parsing a `.gr` graph.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra;
+/// use vasa::io::dimacs::parse_graph;
+/// let text = "c tiny\np sp 3 3\na 1 2 5\na 2 3 7\na 1 3 20\n";
+/// let graph = parse_graph::<u64>(text).unwrap();
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(dijkstra(&graph, 0, 2), Some(12));
+///
+/// let error = parse_graph::<u64>("p sp 2 1\na 1 3 5\n").unwrap_err();
+/// assert_eq!(error.to_string(), "line 2: node 3 not in 1..=2");
+/// ```
+pub fn parse_graph<W: FromStr>(text: &str) -> Result<AdjacencyList<W>, ParseError> {
+    // `p sp <nodes> <arcs>`
+    let (mut problem, counts) = Problem::parse(records(text), &["sp"], 2, "a")?;
+    let n = counts[0];
+
+    let mut graph = AdjacencyList::with_capacity(n);
+    for _ in 0..n {
+        graph.add_node();
+    }
+    while let Some((line, words)) = problem.next()? {
+        let from = node(line, &words, 1, n)?;
+        let to = node(line, &words, 2, n)?;
+        let cost = field(line, &words, 3, "cost")?;
+        graph.add_edge(from, to, cost);
+    }
+    Ok(graph)
+}
+
+
+/// Writing a graph as `.gr` file.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::io::dimacs::write_graph;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 5), (1, 2, 7)]);
+/// let mut out = Vec::new();
+/// write_graph(&graph, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "p sp 3 2\na 1 2 5\na 2 3 7\n");
+/// ```
+pub fn write_graph<W, G, O>(graph: &G, mut out: O) -> io::Result<()>
+    where W: Display,
+          G: Graph<Weight = W>,
+          O: Write
+{
+    let m: usize = (0..graph.node_count()).map(|node| graph.neighbors(node).count()).sum();
+    writeln!(out, "p sp {} {}", graph.node_count(), m)?;
+    for from in 0..graph.node_count() {
+        for Edge { node, cost } in graph.neighbors(from) {
+            writeln!(out, "a {} {} {}", from + 1, node + 1, cost)?;
+        }
+    }
+    Ok(())
+}
+
+
+/// Parsing `.co` coordinates, as `(x, y)` of each node.
+///
+/// Coordinates are usually longitude and latitude in millionths of a degree. They suit the
+/// [`Euclidean`] heuristic once scaled to a lower bound of the costs.
+///
+/// # Example
+/// ```
+/// use vasa::io::dimacs::parse_coordinates;
+/// let coords = parse_coordinates("p aux sp co 2\nv 2 -5 7\nv 1 3 4\n").unwrap();
+/// assert_eq!(coords, vec![(3.0, 4.0), (-5.0, 7.0)]);
+/// ```
+///
+/// [`Euclidean`]: ../../heuristic/struct.Euclidean.html
+pub fn parse_coordinates(text: &str) -> Result<Vec<(f64, f64)>, ParseError> {
+    let (mut problem, counts) = Problem::parse(records(text), &["aux", "sp", "co"], 1, "v")?;
+    let n = counts[0];
+    let mut coords = vec![None; n];
+    while let Some((line, words)) = problem.next()? {
+        let node = node(line, &words, 1, n)?;
+        if coords[node].is_some() {
+            return Err(ParseError::new(line, format!("node {} placed twice", node + 1)));
+        }
+        coords[node] = Some((field(line, &words, 2, "coordinate")?, field(line, &words, 3, "coordinate")?));
+    }
+    // As many distinct nodes as lines were placed, so none is missing
+    Ok(coords.into_iter().map(Option::unwrap).collect())
+}
+
+
+/// Writing `(x, y)` coordinates of each node as `.co` file.
+pub fn write_coordinates<O: Write>(coords: &[(f64, f64)], mut out: O) -> io::Result<()> {
+    writeln!(out, "p aux sp co {}", coords.len())?;
+    for (node, (x, y)) in coords.iter().enumerate() {
+        writeln!(out, "v {} {} {}", node + 1, x, y)?;
+    }
+    Ok(())
+}
+
+
+/// Parsing `.ss` single-source queries, as their source nodes.
+///
+/// Nodes are not checked against any graph, as the file does not tell its size.
+///
+/// # Example
+/// ```
+/// use vasa::io::dimacs::parse_sources;
+/// assert_eq!(parse_sources("p aux sp ss 2\ns 4\ns 1\n").unwrap(), vec![3, 0]);
+/// ```
+pub fn parse_sources(text: &str) -> Result<Vec<usize>, ParseError> {
+    let (mut problem, _) = Problem::parse(records(text), &["aux", "sp", "ss"], 1, "s")?;
+    let mut sources = Vec::new();
+    while let Some((line, words)) = problem.next()? {
+        sources.push(node(line, &words, 1, usize::MAX)?);
+    }
+    Ok(sources)
+}
+
+
+/// Writing single-source queries as `.ss` file.
+pub fn write_sources<O: Write>(sources: &[usize], mut out: O) -> io::Result<()> {
+    writeln!(out, "p aux sp ss {}", sources.len())?;
+    for source in sources {
+        writeln!(out, "s {}", source + 1)?;
+    }
+    Ok(())
+}
+
+
+/// Parsing `.p2p` point-to-point queries, as `(start, goal)` pairs.
+///
+/// # Example
+/// ```
+/// use vasa::io::dimacs::parse_queries;
+/// assert_eq!(parse_queries("c two queries\np aux sp p2p 2\nq 1 3\nq 2 1\n").unwrap(),
+///            vec![(0, 2), (1, 0)]);
+/// ```
+pub fn parse_queries(text: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    let (mut problem, _) = Problem::parse(records(text), &["aux", "sp", "p2p"], 1, "q")?;
+    let mut queries = Vec::new();
+    while let Some((line, words)) = problem.next()? {
+        queries.push((node(line, &words, 1, usize::MAX)?, node(line, &words, 2, usize::MAX)?));
+    }
+    Ok(queries)
+}
+
+
+/// Writing point-to-point queries as `.p2p` file.
+pub fn write_queries<O: Write>(queries: &[(usize, usize)], mut out: O) -> io::Result<()> {
+    writeln!(out, "p aux sp p2p {}", queries.len())?;
+    for (start, goal) in queries {
+        writeln!(out, "q {} {}", start + 1, goal + 1)?;
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn round_trip() {
+        let mut rng = XorShift::new(18);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 20, 50, 100);
+            let mut out = Vec::new();
+            write_graph(&graph, &mut out).unwrap();
+            assert_eq!(parse_graph(&String::from_utf8(out).unwrap()), Ok(graph.clone()));
+
+            let n = graph.node_count();
+            let coords: Vec<_> = (0..n).map(|_| (rng.below(1000) as f64 - 500.0, rng.below(1000) as f64)).collect();
+            let sources: Vec<_> = (0..rng.below(5)).map(|_| rng.below(n)).collect();
+            let queries: Vec<_> = (0..rng.below(5)).map(|_| (rng.below(n), rng.below(n))).collect();
+
+            let mut out = Vec::new();
+            write_coordinates(&coords, &mut out).unwrap();
+            assert_eq!(parse_coordinates(&String::from_utf8(out).unwrap()), Ok(coords));
+            let mut out = Vec::new();
+            write_sources(&sources, &mut out).unwrap();
+            assert_eq!(parse_sources(&String::from_utf8(out).unwrap()), Ok(sources));
+            let mut out = Vec::new();
+            write_queries(&queries, &mut out).unwrap();
+            assert_eq!(parse_queries(&String::from_utf8(out).unwrap()), Ok(queries));
+        }
+    }
+
+    #[test]
+    fn road_network() {
+        let graph = "c 9th DIMACS Implementation Challenge: Shortest Paths\n\
+                     c\n\
+                     p sp 4 5\n\
+                     c arcs\n\
+                     a 1 2 803\n\
+                     a 2 1 803\n\
+                     a 2 3 158\n\
+                     \n\
+                     a 3 4 774\n\
+                     a 1 4 1800\n";
+        let queries = "p aux sp p2p 2\nq 1 4\nq 4 1\n";
+        let graph = parse_graph::<u32>(graph).unwrap();
+        let costs: Vec<_> = parse_queries(queries).unwrap()
+            .into_iter()
+            .map(|(start, goal)| dijkstra(&graph, start, goal))
+            .collect();
+        assert_eq!(costs, vec![Some(1735), None]);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |result: Result<AdjacencyList<u32>, ParseError>| result.unwrap_err();
+        assert_eq!(error(parse_graph("")), ParseError::new(1, "missing `p sp` line"));
+        assert_eq!(error(parse_graph("c\na 1 2 3\n")), ParseError::new(2, "expected `p sp` line"));
+        assert_eq!(error(parse_graph("p sp 2\n")), ParseError::new(1, "missing count"));
+        assert_eq!(error(parse_graph("p sp 2 x\n")), ParseError::new(1, "invalid count `x`"));
+        assert_eq!(error(parse_graph("p sp 2 1\na 1 2 -4\n")), ParseError::new(2, "invalid cost `-4`"));
+        assert_eq!(error(parse_graph("p sp 2 1\na 1 2\n")), ParseError::new(2, "missing cost"));
+        assert_eq!(error(parse_graph("p sp 2 1\na 0 2 1\n")), ParseError::new(2, "node 0 not in 1..=2"));
+        assert_eq!(error(parse_graph("p sp 2 2\nc\na 1 2 1\nc\n")),
+                   ParseError::new(3, "expected 2 `a` lines, found 1"));
+        assert_eq!(error(parse_graph("p sp 2 1\na 1 2 1\na 2 1 1\n")),
+                   ParseError::new(3, "more than 1 `a` lines"));
+        assert_eq!(error(parse_graph("p sp 2 1\nv 1 2 1\n")), ParseError::new(2, "unexpected `v` line"));
+
+        assert_eq!(parse_coordinates("p aux sp co 2\nv 1 0 0\nv 1 0 0\n").unwrap_err(),
+                   ParseError::new(3, "node 1 placed twice"));
+        assert_eq!(parse_sources("p aux sp ss 1\ns 0\n").unwrap_err().line, 2);
+        assert_eq!(parse_queries("p aux sp ss 1\ns 1\n").unwrap_err(),
+                   ParseError::new(1, "expected `p aux sp p2p` line"));
+    }
+}
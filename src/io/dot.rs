@@ -0,0 +1,424 @@
+//! Graphs in the [DOT] language of Graphviz, for drawing them.
+//!
+//! [DOT]: https://graphviz.org/doc/info/lang.html
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::dijkstra::ShortestPath;
+use crate::graph::{AdjacencyList, Graph};
+use super::ParseError;
+
+
+/// Drawing of a graph in DOT, optionally highlighting a path and the nodes settled by a search.
+///
+/// Nodes are named by their number, and edges labeled with their cost. The nodes and edges of
+/// the path are drawn red, settled nodes filled gray. Render the output with e.g.
+/// `dot -Tsvg graph.dot > graph.svg`.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra_path;
+/// use vasa::graph::AdjacencyList;
+/// use vasa::io::dot::Dot;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, 2), (0, 2, 4)]);
+/// let path = dijkstra_path(&graph, 0, 1).unwrap();
+/// let dot = Dot::new(&graph).path(&path).settled(vec![0]).to_string();
+/// assert_eq!(dot, "digraph {
+///     0 [color=red, style=filled, fillcolor=lightgray];
+///     1 [color=red];
+///     2;
+///     0 -> 1 [label=\"1\", color=red, penwidth=2];
+///     0 -> 2 [label=\"4\"];
+///     1 -> 2 [label=\"2\"];
+/// }
+/// ");
+/// ```
+pub struct Dot<'a, G: Graph> {
+    graph: &'a G,
+    path: Option<&'a ShortestPath<G::Weight>>,
+    settled: Vec<bool>,
+}
+
+impl<'a, G: Graph> Dot<'a, G> {
+    /// Creating a new drawing of `graph`, without highlights.
+    pub fn new(graph: &'a G) -> Dot<'a, G> {
+        Dot { graph, path: None, settled: vec![false; graph.node_count()] }
+    }
+
+    /// Highlighting the nodes and edges of `path`.
+    pub fn path(self, path: &'a ShortestPath<G::Weight>) -> Dot<'a, G> {
+        Dot { path: Some(path), ..self }
+    }
+
+    /// Highlighting the settled `nodes`, such as the ones of [`ShortestPathTree::settled`].
+    ///
+    /// [`ShortestPathTree::settled`]: ../../dijkstra/struct.ShortestPathTree.html#method.settled
+    pub fn settled<I>(mut self, nodes: I) -> Dot<'a, G>
+        where I: IntoIterator<Item = usize>
+    {
+        for node in nodes {
+            self.settled[node] = true;
+        }
+        self
+    }
+}
+
+impl<G> Display for Dot<'_, G>
+    where G: Graph,
+          G::Weight: Display + PartialEq
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut on_path = vec![false; self.graph.node_count()];
+        // Edges of the path not drawn yet, as `(from, edge)`, so only one of parallel edges is red
+        let mut path_edges = Vec::new();
+        if let Some(path) = self.path {
+            for &node in &path.nodes {
+                on_path[node] = true;
+            }
+            path_edges.extend(path.nodes.iter().copied().zip(path.edges.iter().copied()));
+        }
+
+        writeln!(f, "digraph {{")?;
+        for (node, &on_path) in on_path.iter().enumerate() {
+            let mut attrs = Vec::new();
+            if on_path {
+                attrs.push("color=red");
+            }
+            if self.settled[node] {
+                attrs.push("style=filled, fillcolor=lightgray");
+            }
+            if attrs.is_empty() {
+                writeln!(f, "    {};", node)?;
+            } else {
+                writeln!(f, "    {} [{}];", node, attrs.join(", "))?;
+            }
+        }
+        for from in 0..self.graph.node_count() {
+            for edge in self.graph.neighbors(from) {
+                write!(f, "    {} -> {} [label=\"{}\"", from, edge.node, edge.cost)?;
+                if let Some(i) = path_edges.iter().position(|&(u, e)| u == from && e == edge) {
+                    path_edges.swap_remove(i);
+                    write!(f, ", color=red, penwidth=2")?;
+                }
+                writeln!(f, "];")?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+
+/// Token of the DOT language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Name, number or quoted string
+    Id(String),
+    /// `->` or `--`
+    EdgeOp,
+    /// Any of `{}[]=;,`
+    Punct(char),
+}
+
+/// Splitting `text` into tokens with their line numbers, dropping comments.
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '#' => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                let start = line;
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => return Err(ParseError::new(start, "unterminated comment")),
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'>') || chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push((line, Token::EdgeOp));
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' => tokens.push((line, Token::Punct(c))),
+            '"' => {
+                let start = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if chars.peek() == Some(&'"') => id.push(chars.next().unwrap()),
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                        None => return Err(ParseError::new(start, "unterminated string")),
+                    }
+                }
+                tokens.push((start, Token::Id(id)));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    id.push(c);
+                    chars.next();
+                }
+                tokens.push((line, Token::Id(id)));
+            }
+            c => return Err(ParseError::new(line, format!("unexpected `{}`", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+
+/// Parser of a token stream, keeping track of the current line for errors.
+struct Parser {
+    tokens: std::vec::IntoIter<(usize, Token)>,
+    peeked: Option<(usize, Token)>,
+    line: usize,
+}
+
+impl Parser {
+    fn peek(&mut self) -> Option<&Token> {
+        if self.peeked.is_none() {
+            self.peeked = self.tokens.next();
+        }
+        self.peeked.as_ref().map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        self.peek();
+        let (line, token) = self.peeked.take()
+            .ok_or_else(|| ParseError::new(self.line, "unexpected end of input"))?;
+        self.line = line;
+        Ok(token)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+
+    fn id(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Id(id) => Ok(id),
+            token => Err(self.error(format!("expected name, found {:?}", token))),
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), ParseError> {
+        match self.next()? {
+            Token::Punct(c) if c == punct => Ok(()),
+            token => Err(self.error(format!("expected `{}`, found {:?}", punct, token))),
+        }
+    }
+
+    /// Skipping a `;` or `,` if there is one.
+    fn separator(&mut self) {
+        if let Some(Token::Punct(';')) | Some(Token::Punct(',')) = self.peek() {
+            self.peeked = None;
+        }
+    }
+
+    /// Attribute lists `[key=value, ...]`, possibly several in a row.
+    fn attributes(&mut self) -> Result<HashMap<String, String>, ParseError> {
+        let mut attrs = HashMap::new();
+        while self.peek() == Some(&Token::Punct('[')) {
+            self.next()?;
+            while self.peek() != Some(&Token::Punct(']')) {
+                let key = self.id()?;
+                self.expect('=')?;
+                let value = self.id()?;
+                attrs.insert(key, value);
+                self.separator();
+            }
+            self.next()?;
+        }
+        Ok(attrs)
+    }
+}
+
+
+/// Parsing a graph from DOT, returning it with the names of its nodes.
+///
+/// Nodes are numbered in the order they are first mentioned. Edges take their cost from the
+/// `weight` attribute, or else from the `label`. Edges of undirected graphs (`graph` with `--`)
+/// are added in both directions. Attribute statements such as `node [shape=box]` are skipped,
+/// subgraphs are not supported.
+///
+/// # Example
+/// ```
+/// use vasa::dijkstra::dijkstra;
+/// use vasa::io::dot::parse_dot;
+/// let text = "digraph roads {
+///     // distances in km
+///     home -> work [label=12];
+///     home -> park -> work [weight=5];
+/// }";
+/// let (graph, names) = parse_dot::<u32>(text).unwrap();
+/// assert_eq!(names, vec!["home", "work", "park"]);
+/// assert_eq!(dijkstra(&graph, 0, 1), Some(10));
+/// ```
+pub fn parse_dot<W: FromStr + Copy>(text: &str) -> Result<(AdjacencyList<W>, Vec<String>), ParseError> {
+    let mut parser = Parser { tokens: tokenize(text)?.into_iter(), peeked: None, line: 1 };
+
+    let mut kind = parser.id()?;
+    if kind == "strict" {
+        kind = parser.id()?;
+    }
+    let directed = match kind.as_str() {
+        "digraph" => true,
+        "graph" => false,
+        _ => return Err(parser.error(format!("expected `digraph` or `graph`, found `{}`", kind))),
+    };
+    if let Some(Token::Id(_)) = parser.peek() {
+        parser.id()?;
+    }
+    parser.expect('{')?;
+
+    let mut graph = AdjacencyList::new();
+    let mut names = Vec::new();
+    let mut nodes = HashMap::new();
+    let mut node = |graph: &mut AdjacencyList<W>, name: String| {
+        *nodes.entry(name.clone()).or_insert_with(|| {
+            names.push(name);
+            graph.add_node()
+        })
+    };
+
+    loop {
+        let id = match parser.next()? {
+            Token::Punct('}') => break,
+            Token::Punct(';') | Token::Punct(',') => continue,
+            Token::Id(id) => id,
+            token => return Err(parser.error(format!("expected statement, found {:?}", token))),
+        };
+        match (id.as_str(), parser.peek()) {
+            ("subgraph", _) => return Err(parser.error("subgraphs are not supported")),
+            // Default attributes, or an attribute of the graph
+            ("node", Some(Token::Punct('['))) | ("edge", Some(Token::Punct('['))) | ("graph", Some(Token::Punct('['))) => {
+                parser.attributes()?;
+            }
+            (_, Some(Token::Punct('='))) => {
+                parser.next()?;
+                parser.id()?;
+            }
+            _ => {
+                let mut chain = vec![node(&mut graph, id)];
+                while parser.peek() == Some(&Token::EdgeOp) {
+                    parser.next()?;
+                    let name = parser.id()?;
+                    chain.push(node(&mut graph, name));
+                }
+                let attrs = parser.attributes()?;
+                if chain.len() > 1 {
+                    let value = attrs.get("weight").or_else(|| attrs.get("label"))
+                        .ok_or_else(|| parser.error("edge without `weight` or `label`"))?;
+                    let cost: W = value.parse()
+                        .map_err(|_| parser.error(format!("invalid cost `{}`", value)))?;
+                    for pair in chain.windows(2) {
+                        graph.add_edge(pair[0], pair[1], cost);
+                        if !directed {
+                            graph.add_edge(pair[1], pair[0], cost);
+                        }
+                    }
+                }
+            }
+        }
+        parser.separator();
+    }
+    if let Some(token) = parser.peek().cloned() {
+        parser.next()?;
+        return Err(parser.error(format!("unexpected {:?} after graph", token)));
+    }
+
+    Ok((graph, names))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra_path;
+    use crate::graph::Edge;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn round_trip() {
+        let mut rng = XorShift::new(19);
+        for _ in 0..100 {
+            let graph = random_graph(&mut rng, 15, 40, 10);
+            let n = graph.node_count();
+            let path = dijkstra_path(&graph, rng.below(n), rng.below(n));
+            let mut dot = Dot::new(&graph).settled((0..n).filter(|_| rng.below(2) == 0));
+            if let Some(path) = &path {
+                dot = dot.path(path);
+            }
+            let (parsed, names) = parse_dot(&dot.to_string()).unwrap();
+            assert_eq!(parsed, graph);
+            assert_eq!(names, (0..n).map(|node| node.to_string()).collect::<Vec<_>>());
+
+            // As many red edges as the path has
+            let red = dot.to_string().matches("penwidth").count();
+            assert_eq!(red, path.map_or(0, |path| path.edges.len()));
+        }
+    }
+
+    #[test]
+    fn parallel_edges() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 3), (0, 1, 3)]);
+        let path = dijkstra_path(&graph, 0, 1).unwrap();
+        assert_eq!(Dot::new(&graph).path(&path).to_string().matches("penwidth").count(), 1);
+    }
+
+    #[test]
+    fn undirected() {
+        let text = "strict graph {\n  node [shape=circle]; rankdir=LR\n  a -- b [label=\"2\"]\n  /* isolated */ c\n}\n";
+        let (graph, names) = parse_dot::<u8>(text).unwrap();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(graph, AdjacencyList::from(vec![vec![Edge { node: 1, cost: 2 }],
+                                                   vec![Edge { node: 0, cost: 2 }],
+                                                   vec![]]));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text| parse_dot::<u32>(text).unwrap_err();
+        assert_eq!(error("digraph {\n a -> b\n}"), ParseError::new(2, "edge without `weight` or `label`"));
+        assert_eq!(error("digraph {\n\n a -> b [label=x]\n}"), ParseError::new(3, "invalid cost `x`"));
+        assert_eq!(error("digraph {\n a -> \"b\n\n"), ParseError::new(2, "unterminated string"));
+        assert_eq!(error("digraph {\n /* a -> b\n"), ParseError::new(2, "unterminated comment"));
+        assert_eq!(error("tree {}"), ParseError::new(1, "expected `digraph` or `graph`, found `tree`"));
+        assert_eq!(error("digraph {\n subgraph x {}\n}"), ParseError::new(2, "subgraphs are not supported"));
+        assert_eq!(error("digraph {\n a ->\n"), ParseError::new(2, "unexpected end of input"));
+        assert_eq!(error("digraph {\n a @ b\n}"), ParseError::new(2, "unexpected `@`"));
+        assert_eq!(error("digraph {}\n}").line, 2);
+    }
+}
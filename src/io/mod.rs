@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+pub mod csv;
+pub mod dimacs;
+pub mod dot;
+
+
+/// Malformed input, with the line the problem was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Number of the offending line, starting at 1
+    pub line: usize,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> ParseError {
+        ParseError { line, message: message.into() }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(ParseError::new(3, "oops").to_string(), "line 3: oops");
+    }
+}
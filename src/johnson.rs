@@ -0,0 +1,164 @@
+use crate::bellman_ford::{bellman_ford, NegativeCycle};
+use crate::dijkstra::{dijkstra_all, ShortestPath, ShortestPathTree};
+use crate::graph::{AdjacencyList, Edge, Graph};
+use crate::weight::Weight;
+
+
+/// Shortest paths between all pairs of nodes, as found by [`johnson`].
+///
+/// Internally this keeps one shortest path tree per source on the reweighted graph, and
+/// translates distances back to the original costs on every query.
+///
+/// [`johnson`]: fn.johnson.html
+#[derive(Debug, Clone)]
+pub struct AllPairs<W = usize> {
+    /// potential[node] = shortest distance to `node` from an additional node connected to all
+    potential: Vec<W>,
+    /// trees[source] = shortest paths from `source` on the reweighted graph
+    trees: Vec<ShortestPathTree<W>>,
+}
+
+impl<W: Weight> AllPairs<W> {
+    /// Return number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Shortest distance from `from` to `to`, `None` if there is no path.
+    pub fn distance(&self, from: usize, to: usize) -> Option<W> {
+        self.trees[from].distance_to(to).map(|cost| self.original_cost(from, to, cost))
+    }
+
+    /// Shortest path from `from` to `to`, `None` if there is no path.
+    pub fn path(&self, from: usize, to: usize) -> Option<ShortestPath<W>> {
+        let mut path = self.trees[from].path_to(to)?;
+        path.cost = self.original_cost(from, to, path.cost);
+        for (&node, edge) in path.nodes.iter().zip(path.edges.iter_mut()) {
+            edge.cost = self.original_cost(node, edge.node, edge.cost);
+        }
+        Some(path)
+    }
+
+    /// Undo reweighting for a path from `from` to `to`.
+    fn original_cost(&self, from: usize, to: usize, cost: W) -> W {
+        cost.checked_sub(self.potential[from])
+            .and_then(|cost| cost.checked_add(self.potential[to]))
+            .expect("original costs are representable")
+    }
+}
+
+
+/// Johnson's algorithm for shortest paths between all pairs of nodes.
+///
+/// Dijkstra's algorithm can not handle negative costs, so the edges are reweighted first. A
+/// [`bellman_ford`] search from an additional node, connected to all others at zero cost, yields
+/// a potential `h` with `cost(u, v) + h(u) - h(v) >= 0` for every edge. Reweighting changes the
+/// cost of every path from `s` to `t` by the same `h(s) - h(t)`, so shortest paths stay shortest.
+/// Then [`dijkstra_all`] runs once from every node.
+///
+/// On sparse graphs this is faster than Floyd-Warshall, taking `O(n m log n)` time.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::johnson::johnson;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 3), (1, 2, -2), (2, 0, 1), (0, 2, 2)]);
+/// let paths = johnson(&graph).unwrap();
+/// assert_eq!(paths.distance(0, 2), Some(1));
+/// assert_eq!(paths.distance(1, 0), Some(-1));
+/// assert_eq!(paths.path(0, 2).unwrap().nodes, vec![0, 1, 2]);
+/// ```
+///
+/// [`bellman_ford`]: ../bellman_ford/fn.bellman_ford.html
+/// [`dijkstra_all`]: ../dijkstra/fn.dijkstra_all.html
+pub fn johnson<W, G>(graph: &G) -> Result<AllPairs<W>, NegativeCycle>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let n = graph.node_count();
+
+    // Connect an additional node `n` to all others
+    let mut extended = AdjacencyList::with_capacity(n + 1);
+    for _ in 0..=n {
+        extended.add_node();
+    }
+    for position in 0..n {
+        for Edge { node, cost } in graph.neighbors(position) {
+            extended.add_edge(position, node, cost);
+        }
+        extended.add_edge(n, position, W::zero());
+    }
+    let tree = bellman_ford(&extended, n)?;
+    let potential: Vec<W> = (0..n)
+        .map(|node| tree.distance_to(node).expect("all nodes are connected"))
+        .collect();
+
+    // Reweight all edges to non-negative costs
+    let mut reweighted = AdjacencyList::with_capacity(n);
+    for _ in 0..n {
+        reweighted.add_node();
+    }
+    for position in 0..n {
+        for Edge { node, cost } in graph.neighbors(position) {
+            let cost = cost.checked_add(potential[position])
+                .and_then(|cost| cost.checked_sub(potential[node]))
+                .expect("reweighted costs are representable");
+            // Rounding errors of floating point costs could make them slightly negative
+            reweighted.add_edge(position, node, cost.max(W::zero()));
+        }
+    }
+
+    let trees = (0..n).map(|source| dijkstra_all(&reweighted, source)).collect();
+
+    Ok(AllPairs { potential, trees })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn negative_cycle() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, -3), (2, 1, 2)]);
+        let mut nodes = johnson(&graph).unwrap_err().nodes;
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2]);
+    }
+
+    #[test]
+    fn like_bellman_ford() {
+        let mut rng = XorShift::new(77);
+        let mut checked = 0;
+        while checked < 300 {
+            let unsigned = random_graph(&mut rng, 10, 25, 20);
+            let graph = AdjacencyList::from_edges((0..unsigned.node_count())
+                .flat_map(|from| unsigned.neighbors(from).iter()
+                          .map(move |edge| (from, edge.node, edge.cost as i64 - 3))));
+
+            let paths = match johnson(&graph) {
+                Ok(paths) => paths,
+                Err(_) => continue,
+            };
+            checked += 1;
+
+            assert_eq!(paths.node_count(), graph.node_count());
+            for from in 0..graph.node_count() {
+                let tree = bellman_ford(&graph, from).unwrap();
+                for to in 0..graph.node_count() {
+                    assert_eq!(paths.distance(from, to), tree.distance_to(to));
+                    if let Some(path) = paths.path(from, to) {
+                        assert_eq!(Some(path.cost), tree.distance_to(to));
+                        assert_eq!(path.nodes.first(), Some(&from));
+                        assert_eq!(path.nodes.last(), Some(&to));
+                        for (&node, edge) in path.nodes.iter().zip(&path.edges) {
+                            assert!(graph.neighbors(node).contains(edge));
+                        }
+                        assert_eq!(path.edges.iter().map(|e| e.cost).sum::<i64>(), path.cost);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::dijkstra::{dijkstra_path, dijkstra_path_masked, Frontier, ShortestPath, State};
+use crate::graph::{Edge, Graph};
+use crate::weight::Weight;
+
+
+/// Yen's algorithm, finding the `k` cheapest loopless paths from `start` to `goal`.
+///
+/// Starting from the shortest path, each further path deviates from one found before: for every
+/// node of the last path found, the spur node, a path is searched from there to `goal` that
+/// avoids the nodes before the spur node and the edges other paths sharing this prefix take
+/// next. The cheapest of all such candidates is the next path.
+///
+/// Paths are distinct by their nodes, using the cheapest of any parallel edges. They come
+/// cheapest first, and fewer than `k` if there are no more loopless paths.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::ksp::yen;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 3, 1), (0, 2, 1), (2, 3, 2), (0, 3, 4), (1, 2, 0)]);
+/// let paths = yen(&graph, 0, 3, 5);
+/// let found: Vec<_> = paths.iter().map(|path| (path.cost, path.nodes.clone())).collect();
+/// assert_eq!(found, vec![(2, vec![0, 1, 3]),
+///                        (3, vec![0, 2, 3]),
+///                        (3, vec![0, 1, 2, 3]),
+///                        (4, vec![0, 3])]);
+/// ```
+pub fn yen<W, G>(graph: &G, start: usize, goal: usize, k: usize) -> Vec<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let mut paths: Vec<ShortestPath<W>> = Vec::new();
+    if k == 0 {
+        return paths;
+    }
+    match dijkstra_path(graph, start, goal) {
+        Some(path) => paths.push(path),
+        None => return paths,
+    }
+
+    // Candidates for the next path, and the nodes of all paths ever found
+    let mut candidates: Vec<ShortestPath<W>> = Vec::new();
+    let mut seen: HashSet<Vec<usize>> = HashSet::new();
+    seen.insert(paths[0].nodes.clone());
+
+    let mut blocked_nodes = vec![false; graph.node_count()];
+    while paths.len() < k {
+        let last = paths.last().unwrap();
+
+        for i in 0..last.nodes.len() - 1 {
+            let spur = last.nodes[i];
+            let root = &last.nodes[..=i];
+
+            // Edges leaving the spur node along paths with the same root
+            let blocked_edges: HashSet<(usize, usize)> = paths.iter()
+                .filter(|path| path.nodes.len() > i + 1 && path.nodes[..=i] == *root)
+                .map(|path| (spur, path.nodes[i + 1]))
+                .collect();
+            // The root may not be visited again
+            for &node in &root[..i] {
+                blocked_nodes[node] = true;
+            }
+            let spur_path = dijkstra_path_masked(graph, spur, goal, &blocked_nodes,
+                                                 |from, to| blocked_edges.contains(&(from, to)));
+            for &node in &root[..i] {
+                blocked_nodes[node] = false;
+            }
+
+            let spur_path = match spur_path {
+                Some(spur_path) => spur_path,
+                None => continue,
+            };
+            let cost = last.edges[..i].iter()
+                .try_fold(spur_path.cost, |cost, edge| cost.checked_add(edge.cost));
+            let cost = match cost {
+                Some(cost) => cost,
+                // Too expensive to even represent
+                None => continue,
+            };
+            let mut nodes = root.to_vec();
+            nodes.extend_from_slice(&spur_path.nodes[1..]);
+            if seen.insert(nodes.clone()) {
+                let mut edges = last.edges[..i].to_vec();
+                edges.extend(spur_path.edges);
+                candidates.push(ShortestPath { cost, nodes, edges });
+            }
+        }
+
+        // Cheapest candidate next, preferring fewer edges among equally cheap ones
+        let best = candidates.iter()
+            .enumerate()
+            .min_by_key(|(_, path)| (path.cost, path.edges.len()))
+            .map(|(i, _)| i);
+        match best {
+            Some(best) => paths.push(candidates.swap_remove(best)),
+            None => break,
+        }
+    }
+
+    paths
+}
+
+
+/// The `k` cheapest walks from `start` to `goal`, which unlike the paths of [`yen`] may visit
+/// nodes more than once.
+///
+/// Finds the same walks as Eppstein's algorithm, up to ties, but in the simpler way of letting
+/// Dijkstra's algorithm settle every node up to `k` times: the `j`-th time a node is settled is
+/// at the end of its `j`-th cheapest walk from `start`. Walks are distinct by their edges, so
+/// parallel edges make different walks.
+///
+/// Edge costs must not be negative.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::ksp::k_shortest_walks;
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 0, 1), (1, 2, 1)]);
+/// let walks = k_shortest_walks(&graph, 0, 2, 3);
+/// let found: Vec<_> = walks.iter().map(|walk| (walk.cost, walk.nodes.len())).collect();
+/// assert_eq!(found, vec![(2, 3), (4, 5), (6, 7)]);
+/// ```
+///
+/// [`yen`]: fn.yen.html
+pub fn k_shortest_walks<W, G>(graph: &G, start: usize, goal: usize, k: usize) -> Vec<ShortestPath<W>>
+    where W: Weight,
+          G: Graph<Weight = W>
+{
+    let mut walks = Vec::new();
+    if k == 0 {
+        return walks;
+    }
+
+    // Walks in the queue, as their last edge and the walk it extends
+    let mut steps: Vec<(Edge<W>, Option<usize>)> = vec![(Edge { node: start, cost: W::zero() }, None)];
+    // settled[node] = number of times `node` has been settled
+    let mut settled = vec![0; graph.node_count()];
+
+    let mut heap: BinaryHeap<State<W>> = BinaryHeap::new();
+    Frontier::push(&mut heap, W::zero(), 0);
+
+    while let Some((cost, step)) = heap.pop_min() {
+        let node = steps[step].0.node;
+        if settled[node] == k {
+            continue;
+        }
+        settled[node] += 1;
+
+        if node == goal {
+            walks.push(unwind(&steps, cost, step));
+            if walks.len() == k {
+                break;
+            }
+        }
+
+        for edge in graph.neighbors(node) {
+            debug_assert!(edge.cost >= W::zero(), "negative edge cost {:?}", edge.cost);
+            let next = match cost.checked_add(edge.cost) {
+                Some(next) => next,
+                // Too expensive to even represent
+                None => continue,
+            };
+            if settled[edge.node] < k {
+                steps.push((edge, Some(step)));
+                Frontier::push(&mut heap, next, steps.len() - 1);
+            }
+        }
+    }
+
+    walks
+}
+
+
+/// Walk ending with `steps[step]`, following the steps it extends back to the start.
+fn unwind<W: Weight>(steps: &[(Edge<W>, Option<usize>)], cost: W, step: usize) -> ShortestPath<W> {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut current = Some(step);
+    while let Some(step) = current {
+        let (edge, prev) = steps[step];
+        nodes.push(edge.node);
+        if prev.is_some() {
+            edges.push(edge);
+        }
+        current = prev;
+    }
+    nodes.reverse();
+    edges.reverse();
+    ShortestPath { cost, nodes, edges }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AdjacencyList;
+    use crate::testutil::{random_graph, XorShift};
+
+    // Costs of all walks continuing `walk` to `goal` of at most `budget` in total, on a graph of
+    // positive costs, with only loopless ones if `simple`. Parallel edges count once for loopless
+    // paths.
+    fn enumerate(graph: &AdjacencyList, walk: &mut Vec<usize>, goal: usize, budget: usize, simple: bool,
+                 cost: usize, found: &mut Vec<usize>) {
+        let node = *walk.last().unwrap();
+        if node == goal {
+            found.push(cost);
+            if simple {
+                return;
+            }
+        }
+        let mut next: Vec<_> = graph.neighbors(node).to_vec();
+        if simple {
+            // Cheapest edge to each neighbor only
+            next.sort_by_key(|edge| (edge.node, edge.cost));
+            next.dedup_by_key(|edge| edge.node);
+        }
+        for edge in next {
+            if (!simple || !walk.contains(&edge.node)) && cost + edge.cost <= budget {
+                walk.push(edge.node);
+                enumerate(graph, walk, goal, budget, simple, cost + edge.cost, found);
+                walk.pop();
+            }
+        }
+    }
+
+    fn assert_walk(graph: &AdjacencyList, walk: &ShortestPath, start: usize, goal: usize) {
+        assert_eq!(walk.nodes.first(), Some(&start));
+        assert_eq!(walk.nodes.last(), Some(&goal));
+        assert_eq!(walk.nodes.len(), walk.edges.len() + 1);
+        for (from, edge) in walk.nodes.iter().zip(&walk.edges) {
+            assert!(graph.neighbors(*from).contains(edge));
+        }
+        assert_eq!(walk.edges.iter().map(|e| e.cost).sum::<usize>(), walk.cost);
+    }
+
+    fn positive_graph(rng: &mut XorShift) -> AdjacencyList {
+        let graph = random_graph(rng, 8, 20, 5);
+        let edges: Vec<_> = (0..graph.node_count())
+            .flat_map(|from| graph.neighbors(from).iter().map(move |edge| (from, edge.node, edge.cost + 1)))
+            .collect();
+        let mut positive = AdjacencyList::from_edges(edges);
+        while positive.node_count() < graph.node_count() {
+            positive.add_node();
+        }
+        positive
+    }
+
+    #[test]
+    fn yen_like_enumeration() {
+        let mut rng = XorShift::new(25);
+        for _ in 0..300 {
+            let graph = positive_graph(&mut rng);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let k = 1 + rng.below(6);
+            let paths = yen(&graph, start, goal, k);
+
+            let mut expected = Vec::new();
+            enumerate(&graph, &mut vec![start], goal, usize::MAX / 2, true, 0, &mut expected);
+            expected.sort();
+            expected.truncate(k);
+            assert_eq!(paths.iter().map(|path| path.cost).collect::<Vec<_>>(), expected);
+
+            let mut nodes = HashSet::new();
+            for path in &paths {
+                assert_walk(&graph, path, start, goal);
+                let distinct: HashSet<_> = path.nodes.iter().collect();
+                assert_eq!(distinct.len(), path.nodes.len(), "{:?} has a loop", path.nodes);
+                assert!(nodes.insert(path.nodes.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn walks_like_enumeration() {
+        let mut rng = XorShift::new(2025);
+        for _ in 0..300 {
+            let graph = positive_graph(&mut rng);
+            let n = graph.node_count();
+            let (start, goal) = (rng.below(n), rng.below(n));
+            let k = 1 + rng.below(6);
+            let walks = k_shortest_walks(&graph, start, goal, k);
+
+            let mut expected = Vec::new();
+            if let Some(last) = walks.last() {
+                enumerate(&graph, &mut vec![start], goal, last.cost, false, 0, &mut expected);
+            }
+            expected.sort();
+            expected.truncate(k);
+            assert_eq!(walks.iter().map(|walk| walk.cost).collect::<Vec<_>>(), expected);
+
+            for walk in &walks {
+                assert_walk(&graph, walk, start, goal);
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (2, 0, 1)]);
+        assert!(yen(&graph, 0, 2, 3).is_empty());
+        assert!(k_shortest_walks(&graph, 0, 2, 3).is_empty());
+        assert!(yen(&graph, 0, 1, 0).is_empty());
+        assert_eq!(yen(&graph, 0, 0, 3).len(), 1);
+    }
+}
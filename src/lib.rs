@@ -11,6 +11,68 @@ pub mod linked_list;
 /// This is version is not hand-optimized.
 pub mod fifo;
 
+/// Binary heap of nodes with a `decrease_key` operation.
+pub mod indexed_heap;
+
+/// Meldable heaps with `decrease_key`: Fibonacci, pairing, binomial and leftist heaps.
+pub mod heap;
+
+/// Monotone priority queue on `usize` keys, as frontier of Dijkstra's algorithm.
+pub mod radix_heap;
+
+/// Edge weights with overflow-checked addition, including totally ordered floats.
+pub mod weight;
+
+/// The `Graph` trait shared by all graph representations, adjacency lists and implicit graphs.
+pub mod graph;
+
+/// Compact, immutable graphs in compressed sparse row format.
+pub mod csr;
+
+/// Estimates of the remaining distance, guiding A* search.
+pub mod heuristic;
+
+/// Shortest paths with Dijkstra's algorithm, in one or both directions, and A*.
+pub mod dijkstra;
+
+/// Dijkstra's algorithm step by step, as an iterator of events.
+pub mod events;
+
+/// A* with landmarks and the triangle inequality, a heuristic for graphs without coordinates.
+pub mod alt;
+
+/// The `k` shortest paths between two nodes, loopless with Yen's algorithm or as walks.
+pub mod ksp;
+
+/// Breadth-first search, counting steps instead of costs.
+pub mod bfs;
+
+/// Shortest paths for small integer costs, with 0-1 BFS and Dial's bucket queue.
+pub mod dial;
+
+/// Shortest paths with negative edge costs, detecting negative cycles.
+pub mod bellman_ford;
+
+/// Shortest paths between all pairs of nodes on sparse graphs.
+pub mod johnson;
+
+/// Dense graphs as distance matrices, and Floyd-Warshall shortest paths between all pairs.
+pub mod matrix;
+
+/// Reading and writing graphs as DIMACS benchmarks, Graphviz DOT and CSV edge lists.
+pub mod io;
+
+/// Implicit grid graphs with A* and Jump Point Search, and the MovingAI benchmark formats.
+pub mod grid;
+
+/// Contraction hierarchies, preprocessing a graph for fast shortest path queries.
+pub mod ch;
+
+mod rng;
+
+#[cfg(test)]
+mod testutil;
+
 // */
 
 
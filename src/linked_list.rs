@@ -3,10 +3,9 @@ use std::ops::{Index, IndexMut};
 use std::fmt;
 
 
-///! [`Node`]: struct.Node.html
-
-
 /// Index for [`Node`]s, with additional functionality.
+///
+/// [`Node`]: struct.Node.html
 #[derive(PartialEq, Copy, Clone, Eq)]
 pub struct Pointer(usize);
 
@@ -37,10 +36,10 @@ impl fmt::Debug for Pointer {
 }
 
 
-///! [`Pointer`]: struct.Pointer.html
-///! [`LinkedList`]: struct.LinkedList.html
-
 /// [`LinkedList`]-Element, referencing and indexed by [`Pointer`].
+///
+/// [`Pointer`]: struct.Pointer.html
+/// [`LinkedList`]: struct.LinkedList.html
 #[derive(PartialEq, Copy, Clone)]
 pub struct Node<T>
     where T: Copy
@@ -70,17 +69,17 @@ impl<T> Node<T>
         Node {
             prev: Pointer::null(),
             next: Pointer::null(),
-            elem: elem,
+            elem,
         }
     }
 }
 
 
 
-///! [`Pointer`]: struct.Pointer.html
-///! [`Node`]: struct.Node.html
-
 /// Main datastructure, organizing [`Node`]s with [`Pointer`]s.
+///
+/// [`Pointer`]: struct.Pointer.html
+/// [`Node`]: struct.Node.html
 #[derive(Debug, PartialEq)]
 pub struct LinkedList<T>
     where T: Copy
@@ -133,6 +132,15 @@ impl<T> IndexMut<Pointer> for LinkedList<T>
 }
 
 
+impl<T> Default for LinkedList<T>
+    where T: Copy
+{
+    fn default() -> LinkedList<T> {
+        LinkedList::new()
+    }
+}
+
+
 impl<T> LinkedList<T>
     where T: Copy
 {
@@ -230,9 +238,9 @@ impl<T> LinkedList<T>
         let next = self[ptr].next;
         let node = self.insert(
             Node {
-                next: next,
+                next,
                 prev: ptr,
-                elem: elem,
+                elem,
             });
         self[ptr].next = node;
         if next.is_null() {
@@ -260,8 +268,8 @@ impl<T> LinkedList<T>
         let node = self.insert(
             Node {
                 next: ptr,
-                prev: prev,
-                elem: elem,
+                prev,
+                elem,
             });
         self[ptr].prev = node;
         if prev.is_null() {
@@ -517,6 +525,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_conversion)]
     fn iterator() {
         let mut ll: LinkedList<i32> = LinkedList::new();
         let p = ll.push_back(3);
@@ -0,0 +1,311 @@
+use std::ops::Index;
+
+use crate::bellman_ford::{bellman_ford, NegativeCycle};
+use crate::dijkstra::ShortestPath;
+use crate::graph::{AdjacencyList, Edge, Graph};
+use crate::weight::Weight;
+
+
+/// Dense directed graph, storing the cost of going from any node to any other in one matrix.
+///
+/// Entries are `None` where there is no edge. Only the cheapest of parallel edges is kept.
+/// Taking `n * n` memory, this only pays off for small or dense graphs, where an adjacency list
+/// would have to allocate a list for every node anyway.
+///
+/// # Example
+/// ```
+/// use vasa::matrix::DistanceMatrix;
+/// let mut matrix = DistanceMatrix::new(2);
+/// matrix.add_edge(0, 1, 5);
+/// matrix.add_edge(0, 1, 3);
+/// assert_eq!(matrix[(0, 1)], Some(3));
+/// assert_eq!(matrix[(1, 0)], None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix<W = usize> {
+    /// Number of nodes
+    n: usize,
+    /// cost[from * n + to] = cost of going from `from` to `to`
+    cost: Vec<Option<W>>,
+}
+
+impl<W> Index<(usize, usize)> for DistanceMatrix<W> {
+    type Output = Option<W>;
+
+    fn index(&self, (from, to): (usize, usize)) -> &Option<W> {
+        assert!(from < self.n && to < self.n, "unknown edge {} -> {}", from, to);
+        &self.cost[from * self.n + to]
+    }
+}
+
+impl<W: Weight> From<&AdjacencyList<W>> for DistanceMatrix<W> {
+    fn from(graph: &AdjacencyList<W>) -> DistanceMatrix<W> {
+        let mut matrix = DistanceMatrix::new(graph.node_count());
+        for from in 0..graph.node_count() {
+            for Edge { node, cost } in graph.neighbors(from) {
+                matrix.add_edge(from, *node, *cost);
+            }
+        }
+        matrix
+    }
+}
+
+impl<W: Weight> From<&DistanceMatrix<W>> for AdjacencyList<W> {
+    fn from(matrix: &DistanceMatrix<W>) -> AdjacencyList<W> {
+        let mut graph = AdjacencyList::with_capacity(matrix.n);
+        for _ in 0..matrix.n {
+            graph.add_node();
+        }
+        for from in 0..matrix.n {
+            for to in 0..matrix.n {
+                if let Some(cost) = matrix[(from, to)] {
+                    graph.add_edge(from, to, cost);
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<W: Weight> DistanceMatrix<W> {
+    /// Creating a new `DistanceMatrix` with `n` nodes, but no edges.
+    pub fn new(n: usize) -> DistanceMatrix<W> {
+        DistanceMatrix { n, cost: vec![None; n * n] }
+    }
+
+    /// Return number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Adding a directed edge from `from` to `to`, unless there is a cheaper one already.
+    ///
+    /// # Panics
+    /// If either node does not exist.
+    pub fn add_edge(&mut self, from: usize, to: usize, cost: W) {
+        assert!(from < self.n && to < self.n, "unknown edge {} -> {}", from, to);
+        let entry = &mut self.cost[from * self.n + to];
+        if entry.is_none_or(|old| cost < old) {
+            *entry = Some(cost);
+        }
+    }
+}
+
+impl<W: Weight> Graph for DistanceMatrix<W> {
+    type Weight = W;
+
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Scanning the row of `node`, which takes `O(n)` time however few edges it has.
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = Edge<W>> {
+        let row = &self.cost[node * self.n..(node + 1) * self.n];
+        row.iter()
+            .enumerate()
+            .filter_map(|(to, cost)| cost.map(|cost| Edge { node: to, cost }))
+    }
+
+    /// Scanning the column of `node`, which is as cheap as scanning its row.
+    fn reverse_neighbors(&self, node: usize) -> Option<impl Iterator<Item = Edge<W>>> {
+        assert!(node < self.n, "unknown node {}", node);
+        Some((0..self.n).filter_map(move |from| {
+            self.cost[from * self.n + node].map(|cost| Edge { node: from, cost })
+        }))
+    }
+}
+
+
+/// Shortest paths between all pairs of nodes, as found by [`floyd_warshall`].
+///
+/// [`floyd_warshall`]: fn.floyd_warshall.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllPairsMatrix<W = usize> {
+    /// Shortest distance between each pair of nodes
+    dist: DistanceMatrix<W>,
+    /// next[from * n + to] = node following `from` on the shortest path to `to`
+    next: Vec<Option<usize>>,
+}
+
+impl<W: Weight> AllPairsMatrix<W> {
+    /// Shortest distances between all nodes.
+    pub fn distances(&self) -> &DistanceMatrix<W> {
+        &self.dist
+    }
+
+    /// Shortest distance from `from` to `to`, `None` if there is no path.
+    pub fn distance(&self, from: usize, to: usize) -> Option<W> {
+        self.dist[(from, to)]
+    }
+
+    /// Node following `from` on the shortest path to `to`, `None` if there is no path.
+    ///
+    /// The next hop of a node to itself is the node itself.
+    pub fn next_hop(&self, from: usize, to: usize) -> Option<usize> {
+        self.next[from * self.dist.n + to]
+    }
+
+    /// Shortest path from `from` to `to`, `None` if there is no path.
+    ///
+    /// Follows next hops towards `to`. Parts of shortest paths are shortest paths themselves, so
+    /// the cost of each edge is the distance between its nodes.
+    pub fn path(&self, from: usize, to: usize) -> Option<ShortestPath<W>> {
+        let cost = self.distance(from, to)?;
+        let mut nodes = vec![from];
+        let mut edges = Vec::new();
+
+        let mut node = from;
+        while node != to {
+            let next = self.next_hop(node, to).expect("nodes on a path have a next hop");
+            let cost = self.distance(node, next).expect("next hops are connected");
+            edges.push(Edge { node: next, cost });
+            nodes.push(next);
+            node = next;
+        }
+
+        Some(ShortestPath { cost, nodes, edges })
+    }
+}
+
+
+/// Floyd-Warshall algorithm for shortest paths between all pairs of nodes.
+///
+/// In step `k`, every path is allowed to pass through the nodes `0..=k`. Going from `i` to `j`
+/// via `k` improves on the best path known if `dist[i][k] + dist[k][j] < dist[i][j]`, and then the
+/// next hop from `i` towards `j` is the one towards `k`. Taking `O(n^3)` time regardless of the
+/// number of edges, this suits small and dense graphs.
+///
+/// Negative costs are allowed. A node with a negative distance to itself lies on a negative
+/// cycle, which is returned as error.
+///
+/// # Example
+/// ```
+/// use vasa::graph::AdjacencyList;
+/// use vasa::matrix::{floyd_warshall, DistanceMatrix};
+/// let graph = AdjacencyList::from_edges(vec![(0, 1, 3), (1, 2, -2), (2, 0, 1), (0, 2, 2)]);
+/// let paths = floyd_warshall(&DistanceMatrix::from(&graph)).unwrap();
+/// assert_eq!(paths.distance(0, 2), Some(1));
+/// assert_eq!(paths.next_hop(0, 2), Some(1));
+/// assert_eq!(paths.path(1, 0).unwrap().nodes, vec![1, 2, 0]);
+/// ```
+pub fn floyd_warshall<W: Weight>(matrix: &DistanceMatrix<W>) -> Result<AllPairsMatrix<W>, NegativeCycle> {
+    let n = matrix.n;
+    let mut dist = matrix.clone();
+    let mut next: Vec<Option<usize>> = (0..n * n)
+        .map(|i| dist.cost[i].map(|_| i % n))
+        .collect();
+
+    // Staying at a node is free, unless there is a negative self-loop
+    for node in 0..n {
+        let stay = &mut dist.cost[node * n + node];
+        if stay.is_none_or(|cost| cost >= W::zero()) {
+            *stay = Some(W::zero());
+            next[node * n + node] = Some(node);
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let to_k = match dist.cost[i * n + k] {
+                Some(cost) => cost,
+                None => continue,
+            };
+            for j in 0..n {
+                let via_k = match dist.cost[k * n + j].and_then(|cost| to_k.checked_add(cost)) {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+                if dist.cost[i * n + j].is_none_or(|cost| via_k < cost) {
+                    dist.cost[i * n + j] = Some(via_k);
+                    next[i * n + j] = next[i * n + k];
+                }
+            }
+        }
+
+        // Stop before costs on a negative cycle spiral down
+        if let Some(node) = (0..n).find(|&node| dist.cost[node * n + node] < Some(W::zero())) {
+            let cycle = bellman_ford(matrix, node).unwrap_err();
+            return Err(cycle);
+        }
+    }
+
+    Ok(AllPairsMatrix { dist, next })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::johnson::johnson;
+    use crate::testutil::{random_graph, XorShift};
+
+    #[test]
+    fn conversion() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (0, 1, 2), (1, 2, 7), (2, 2, 1)]);
+        let matrix = DistanceMatrix::from(&graph);
+        assert_eq!(matrix.node_count(), 3);
+        assert_eq!(matrix[(0, 1)], Some(2));
+        assert_eq!(matrix[(1, 2)], Some(7));
+        assert_eq!(matrix[(2, 2)], Some(1));
+        assert_eq!(matrix[(1, 0)], None);
+        assert_eq!(AdjacencyList::from(&matrix),
+                   AdjacencyList::from_edges(vec![(0, 1, 2), (1, 2, 7), (2, 2, 1)]));
+    }
+
+    #[test]
+    fn neighbors() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 4), (0, 1, 2), (1, 2, 7), (2, 0, 1), (2, 2, 1)]);
+        let matrix = DistanceMatrix::from(&graph);
+        assert_eq!(Graph::node_count(&matrix), 3);
+        assert!(matrix.neighbors(0).eq(vec![Edge { node: 1, cost: 2 }]));
+        assert!(matrix.neighbors(2).eq(vec![Edge { node: 0, cost: 1 }, Edge { node: 2, cost: 1 }]));
+        let reverse: Vec<_> = matrix.reverse_neighbors(2).unwrap().collect();
+        assert_eq!(reverse, vec![Edge { node: 1, cost: 7 }, Edge { node: 2, cost: 1 }]);
+        assert_eq!(matrix.reverse_neighbors(1).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn negative_cycle() {
+        let graph = AdjacencyList::from_edges(vec![(0, 1, 1), (1, 2, -3), (2, 1, 2), (2, 3, 1)]);
+        let mut nodes = floyd_warshall(&DistanceMatrix::from(&graph)).unwrap_err().nodes;
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2]);
+
+        let graph = AdjacencyList::from_edges(vec![(0, 0, -1)]);
+        assert_eq!(floyd_warshall(&DistanceMatrix::from(&graph)).unwrap_err().nodes, vec![0]);
+    }
+
+    #[test]
+    fn like_johnson() {
+        let mut rng = XorShift::new(31337);
+        let mut checked = 0;
+        for _ in 0..1000 {
+            let unsigned = random_graph(&mut rng, 10, 30, 20);
+            let graph = AdjacencyList::from_edges((0..unsigned.node_count())
+                .flat_map(|from| unsigned.neighbors(from).iter()
+                          .map(move |edge| (from, edge.node, edge.cost as i64 - 3))));
+            let matrix = DistanceMatrix::from(&graph);
+
+            let (paths, expected) = match (floyd_warshall(&matrix), johnson(&graph)) {
+                (Ok(paths), Ok(expected)) => (paths, expected),
+                (Err(_), Err(_)) => continue,
+                (paths, expected) => panic!("disagreement: {:?} and {:?}", paths, expected),
+            };
+            checked += 1;
+
+            for from in 0..graph.node_count() {
+                for to in 0..graph.node_count() {
+                    assert_eq!(paths.distance(from, to), expected.distance(from, to));
+                    if let Some(path) = paths.path(from, to) {
+                        assert_eq!(path.nodes.last(), Some(&to));
+                        for (&node, edge) in path.nodes.iter().zip(&path.edges) {
+                            assert!(graph.neighbors(node).contains(edge));
+                        }
+                        assert_eq!(path.edges.iter().map(|e| e.cost).sum::<i64>(), path.cost);
+                    }
+                }
+            }
+        }
+        assert!(checked > 100);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::dijkstra::Frontier;
+
+
+/// Number of buckets: one for keys equal to the last popped one, and one for each bit in which
+/// a key may first differ from it.
+const BUCKETS: usize = usize::BITS as usize + 1;
+
+
+/// Monotone min-heap of items with `usize` keys, where no key pushed is smaller than the last
+/// key popped.
+///
+/// Items are kept in buckets by the highest bit in which their key differs from the last key
+/// popped, `last`. Bucket 0 holds keys equal to `last`, bucket `i` keys sharing all bits above
+/// bit `i - 1` with it. When bucket 0 runs empty, the first non-empty bucket is emptied into the
+/// lower ones, with its smallest key as the new `last`. Every item only moves down, so each
+/// item costs `O(log C)` time in total, for keys below `C`.
+///
+/// This fits Dijkstra's algorithm with integer costs, which only pushes keys at least as large
+/// as the one just popped. Pushing a smaller key is rejected in debug builds.
+///
+/// # Example
+/// ```
+/// use vasa::radix_heap::RadixHeap;
+/// let mut heap = RadixHeap::new();
+/// heap.push(5, 'a');
+/// heap.push(3, 'b');
+/// assert_eq!(heap.pop(), Some((3, 'b')));
+/// heap.push(4, 'c');
+/// assert_eq!(heap.pop(), Some((4, 'c')));
+/// assert_eq!(heap.pop(), Some((5, 'a')));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RadixHeap<T> {
+    /// buckets[i] = items whose key first differs from `last` in bit `i - 1`, as `(key, elem)`
+    buckets: Vec<Vec<(usize, T)>>,
+    /// Last key popped, a lower bound for all keys in the heap
+    last: usize,
+    len: usize,
+}
+
+impl<T> Default for RadixHeap<T> {
+    fn default() -> RadixHeap<T> {
+        RadixHeap::new()
+    }
+}
+
+impl<T> RadixHeap<T> {
+    /// Creating a new and empty `RadixHeap`.
+    pub fn new() -> RadixHeap<T> {
+        RadixHeap {
+            buckets: (0..BUCKETS).map(|_| Vec::new()).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    /// Return number of items in the heap.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bucket for `key`, relative to `last`.
+    fn bucket(&self, key: usize) -> usize {
+        (usize::BITS - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    /// Adding `elem` with the given `key`.
+    ///
+    /// `key` must not be smaller than the last key popped, which is checked in debug builds.
+    pub fn push(&mut self, key: usize, elem: T) {
+        debug_assert!(key >= self.last, "non-monotone push of {} after popping {}", key, self.last);
+        let bucket = self.bucket(key);
+        self.buckets[bucket].push((key, elem));
+        self.len += 1;
+    }
+
+    /// Smallest key in the heap.
+    ///
+    /// Takes `&mut self`, as the items with the smallest key may have to be moved to bucket 0
+    /// first, which also makes the smallest key the lower bound for later pushes.
+    pub fn peek_key(&mut self) -> Option<usize> {
+        self.refill();
+        self.buckets[0].last().map(|&(key, _)| key)
+    }
+
+    /// Removing an item with the smallest key, returning it and its key.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        self.refill();
+        let item = self.buckets[0].pop()?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Make sure bucket 0 is not empty, unless the whole heap is.
+    fn refill(&mut self) {
+        if !self.buckets[0].is_empty() {
+            return;
+        }
+        let i = match (1..BUCKETS).find(|&i| !self.buckets[i].is_empty()) {
+            Some(i) => i,
+            None => return,
+        };
+
+        // All keys of the first non-empty bucket share their higher bits, so their differences
+        // to the smallest of them only show in lower bits, placing them in lower buckets
+        self.last = self.buckets[i].iter().map(|&(key, _)| key).min().unwrap();
+        let items = std::mem::take(&mut self.buckets[i]);
+        for (key, elem) in items {
+            let bucket = self.bucket(key);
+            debug_assert!(bucket < i);
+            self.buckets[bucket].push((key, elem));
+        }
+    }
+}
+
+impl Frontier<usize> for RadixHeap<usize> {
+    fn push(&mut self, cost: usize, node: usize) {
+        RadixHeap::push(self, cost, node);
+    }
+
+    fn pop_min(&mut self) -> Option<(usize, usize)> {
+        self.pop()
+    }
+
+    fn peek_min(&mut self) -> Option<usize> {
+        self.peek_key()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn buckets() {
+        let mut heap = RadixHeap::new();
+        heap.push(0, ());
+        heap.push(1, ());
+        heap.push(6, ());
+        heap.push(7, ());
+        assert_eq!(heap.buckets[0].len(), 1);
+        assert_eq!(heap.buckets[1].len(), 1);
+        assert_eq!(heap.buckets[3].len(), 2);
+        assert_eq!(heap.pop(), Some((0, ())));
+        assert_eq!(heap.pop(), Some((1, ())));
+        // 6 and 7 are redistributed relative to 6
+        assert_eq!(heap.peek_key(), Some(6));
+        assert_eq!(heap.buckets[1].len(), 1);
+        assert_eq!(heap.len(), 2);
+    }
+
+    #[test]
+    fn large_keys() {
+        let mut heap = RadixHeap::new();
+        heap.push(usize::MAX, 'a');
+        heap.push(usize::MAX - 1, 'b');
+        heap.push(1 << 40, 'c');
+        assert_eq!(heap.pop(), Some((1 << 40, 'c')));
+        assert_eq!(heap.pop(), Some((usize::MAX - 1, 'b')));
+        assert_eq!(heap.pop(), Some((usize::MAX, 'a')));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn monotone() {
+        let mut rng = XorShift::new(21);
+        for _ in 0..200 {
+            let mut heap = RadixHeap::new();
+            let mut keys = Vec::new();
+            let mut last = 0;
+            for _ in 0..100 {
+                if rng.below(3) == 0 {
+                    keys.sort();
+                    keys.reverse();
+                    let expected = keys.pop();
+                    assert_eq!(heap.pop().map(|(key, _)| key), expected);
+                    last = expected.unwrap_or(last);
+                } else {
+                    let key = last + rng.below(1000);
+                    heap.push(key, ());
+                    keys.push(key);
+                }
+                assert_eq!(heap.len(), keys.len());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "non-monotone")]
+    #[cfg(debug_assertions)]
+    fn non_monotone() {
+        let mut heap = RadixHeap::new();
+        heap.push(5, ());
+        heap.pop();
+        heap.push(4, ());
+    }
+}
@@ -0,0 +1,24 @@
+/// Small xorshift pseudo-random number generator, good enough for test inputs and
+/// picking landmarks.
+pub(crate) struct XorShift(u64);
+
+impl XorShift {
+    /// Creating a new generator from a non-zero `seed`.
+    pub fn new(seed: u64) -> XorShift {
+        assert_ne!(seed, 0);
+        XorShift(seed)
+    }
+
+    /// Next pseudo-random number.
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Pseudo-random number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
@@ -0,0 +1,22 @@
+use crate::graph::AdjacencyList;
+
+pub(crate) use crate::rng::XorShift;
+
+
+/// Random directed graph with `1..=max_nodes` nodes, up to `max_edges` edges and costs in
+/// `0..=max_cost`. Parallel edges and self-loops may occur.
+pub fn random_graph(rng: &mut XorShift, max_nodes: usize, max_edges: usize, max_cost: usize) -> AdjacencyList {
+    let n = 1 + rng.below(max_nodes);
+    let m = rng.below(max_edges + 1);
+    let mut graph = AdjacencyList::with_capacity(n);
+    for _ in 0..n {
+        graph.add_node();
+    }
+    for _ in 0..m {
+        let from = rng.below(n);
+        let to = rng.below(n);
+        let cost = rng.below(max_cost + 1);
+        graph.add_edge(from, to, cost);
+    }
+    graph
+}
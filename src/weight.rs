@@ -0,0 +1,218 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+
+/// Cost of an edge, or of a path made of edges.
+///
+/// Weights are totally ordered and start at `zero`. Adding weights never overflows silently:
+/// `checked_add` reports an overflow with `None`, `saturating_add` stays at the largest value.
+///
+/// # Example
+/// ```
+/// use vasa::weight::Weight;
+/// assert_eq!(<u8 as Weight>::checked_add(200, 100), None);
+/// assert_eq!(<u8 as Weight>::saturating_add(200, 100), 255);
+/// assert_eq!(<i32 as Weight>::zero(), 0);
+/// ```
+pub trait Weight: Copy + Ord + fmt::Debug {
+    /// Weight of the empty path.
+    fn zero() -> Self;
+
+    /// Largest weight, which `saturating_add` stays at.
+    fn max_value() -> Self;
+
+    /// Sum of two weights, or `None` if it can not be represented.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Sum of two weights, clamped to the representable range.
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// Difference of two weights, or `None` if it can not be represented.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+
+macro_rules! impl_weight_int {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                #[inline]
+                fn zero() -> $t {
+                    0
+                }
+
+                #[inline]
+                fn max_value() -> $t {
+                    <$t>::MAX
+                }
+
+                #[inline]
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    <$t>::checked_add(self, other)
+                }
+
+                #[inline]
+                fn saturating_add(self, other: $t) -> $t {
+                    <$t>::saturating_add(self, other)
+                }
+
+                #[inline]
+                fn checked_sub(self, other: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+        )*
+    }
+}
+
+impl_weight_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+
+/// Floating point number with a total ordering, usable as [`Weight`].
+///
+/// Ordering and equality follow `total_cmp`, so `NaN` is larger than any other value and
+/// `-0.0 < 0.0`. Sums that are not finite count as overflowing.
+///
+/// # Example
+/// ```
+/// use vasa::weight::{OrderedFloat, Weight};
+/// let a = OrderedFloat(1.5);
+/// let b = OrderedFloat(2.25);
+/// assert!(a < b);
+/// assert_eq!(a.checked_add(b), Some(OrderedFloat(3.75)));
+/// assert_eq!(OrderedFloat(f64::MAX).checked_add(OrderedFloat(f64::MAX)), None);
+/// ```
+///
+/// [`Weight`]: trait.Weight.html
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OrderedFloat<F>(pub F);
+
+
+macro_rules! impl_weight_float {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq for OrderedFloat<$t> {
+                fn eq(&self, other: &OrderedFloat<$t>) -> bool {
+                    self.cmp(other) == Ordering::Equal
+                }
+            }
+
+            impl Eq for OrderedFloat<$t> {}
+
+            impl Ord for OrderedFloat<$t> {
+                fn cmp(&self, other: &OrderedFloat<$t>) -> Ordering {
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl PartialOrd for OrderedFloat<$t> {
+                fn partial_cmp(&self, other: &OrderedFloat<$t>) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl From<$t> for OrderedFloat<$t> {
+                fn from(f: $t) -> OrderedFloat<$t> {
+                    OrderedFloat(f)
+                }
+            }
+
+            impl Add for OrderedFloat<$t> {
+                type Output = OrderedFloat<$t>;
+
+                fn add(self, other: OrderedFloat<$t>) -> OrderedFloat<$t> {
+                    OrderedFloat(self.0 + other.0)
+                }
+            }
+
+            impl Sub for OrderedFloat<$t> {
+                type Output = OrderedFloat<$t>;
+
+                fn sub(self, other: OrderedFloat<$t>) -> OrderedFloat<$t> {
+                    OrderedFloat(self.0 - other.0)
+                }
+            }
+
+            impl fmt::Display for OrderedFloat<$t> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl Weight for OrderedFloat<$t> {
+                #[inline]
+                fn zero() -> OrderedFloat<$t> {
+                    OrderedFloat(0.0)
+                }
+
+                #[inline]
+                fn max_value() -> OrderedFloat<$t> {
+                    OrderedFloat(<$t>::MAX)
+                }
+
+                #[inline]
+                fn checked_add(self, other: OrderedFloat<$t>) -> Option<OrderedFloat<$t>> {
+                    let sum = self.0 + other.0;
+                    if sum.is_finite() {
+                        Some(OrderedFloat(sum))
+                    } else {
+                        None
+                    }
+                }
+
+                #[inline]
+                fn saturating_add(self, other: OrderedFloat<$t>) -> OrderedFloat<$t> {
+                    let sum = self.0 + other.0;
+                    if sum.is_nan() {
+                        OrderedFloat(<$t>::INFINITY)
+                    } else {
+                        OrderedFloat(sum.max(<$t>::MIN).min(<$t>::MAX))
+                    }
+                }
+
+                #[inline]
+                fn checked_sub(self, other: OrderedFloat<$t>) -> Option<OrderedFloat<$t>> {
+                    self.checked_add(OrderedFloat(-other.0))
+                }
+            }
+        )*
+    }
+}
+
+impl_weight_float!(f32, f64);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int_overflow() {
+        assert_eq!(Weight::checked_add(u32::MAX, 1), None);
+        assert_eq!(Weight::checked_add(i64::MIN, -1), None);
+        assert_eq!(Weight::saturating_add(usize::MAX - 1, 5), usize::MAX);
+        assert_eq!(Weight::saturating_add(-100i8, -100), i8::MIN);
+        assert_eq!(Weight::checked_sub(3u16, 4), None);
+        assert_eq!(Weight::checked_sub(3i16, 4), Some(-1));
+    }
+
+    #[test]
+    fn float_order() {
+        let mut v = [OrderedFloat(2.0), OrderedFloat(f64::NAN), OrderedFloat(-1.0), OrderedFloat(0.5)];
+        v.sort();
+        assert_eq!(&v[..3], &[OrderedFloat(-1.0), OrderedFloat(0.5), OrderedFloat(2.0)]);
+        assert!(v[3].0.is_nan());
+    }
+
+    #[test]
+    fn float_overflow() {
+        let max = OrderedFloat(f32::MAX);
+        assert_eq!(max.checked_add(max), None);
+        assert_eq!(max.saturating_add(max), max);
+        assert_eq!(OrderedFloat::<f32>::max_value(), max);
+        assert_eq!(OrderedFloat(f64::INFINITY).checked_add(OrderedFloat(0.0)), None);
+        assert_eq!(OrderedFloat(0.25f64).saturating_add(OrderedFloat(0.5)), OrderedFloat(0.75));
+        assert_eq!(OrderedFloat(-f64::MAX).checked_sub(OrderedFloat(f64::MAX)), None);
+    }
+}